@@ -21,6 +21,42 @@ macro_rules! impl_converter {
                 autarkie::OwnedSlice::from(bytes)
             }
         }
+
+        impl autarkie::ToProvenance<$t> for FuzzDataTargetBytesConverter {}
+    };
+    // Render through an `autarkie::Renderer`, so cmplog knows which bytes each node rendered.
+    // eg: render = |input: &Query, renderer: &mut autarkie::Renderer| { .. }
+    ($t:ty, render = $render:expr) => {
+        #[derive(Debug, Clone)]
+        pub struct FuzzDataTargetBytesConverter;
+
+        impl FuzzDataTargetBytesConverter {
+            pub fn new() -> Self {
+                Self
+            }
+        }
+
+        impl autarkie::ToTargetBytes<$t> for FuzzDataTargetBytesConverter {
+            fn to_target_bytes<'a>(&mut self, input: &'a $t) -> autarkie::OwnedSlice<'a, u8> {
+                let mut renderer = autarkie::Renderer::new();
+                $render(input, &mut renderer);
+                let (bytes, _) = renderer.finish();
+                let bytes = if bytes.len() == 0 {
+                    vec![0, 0, 0, 0]
+                } else {
+                    bytes
+                };
+                autarkie::OwnedSlice::from(bytes)
+            }
+        }
+
+        impl autarkie::ToProvenance<$t> for FuzzDataTargetBytesConverter {
+            fn to_provenance(&mut self, input: &$t) -> Option<(Vec<u8>, autarkie::Provenance)> {
+                let mut renderer = autarkie::Renderer::new();
+                $render(input, &mut renderer);
+                Some(renderer.finish())
+            }
+        }
     };
     // We may want to render to bytes manually (eg: to_string) so we offer the possibility of a closure too.
     ($t:ty, $closure:expr) => {
@@ -44,6 +80,8 @@ macro_rules! impl_converter {
                 autarkie::OwnedSlice::from(bytes)
             }
         }
+
+        impl autarkie::ToProvenance<$t> for FuzzDataTargetBytesConverter {}
     };
 }
 
@@ -96,6 +134,12 @@ macro_rules! fuzz_afl {
         $crate::fuzz_afl_inner!($t);
         $crate::impl_hash!($t);
     };
+    ($t:ty, render = $render:expr) => {
+        $crate::impl_input!($t);
+        $crate::impl_converter!($t, render = $render);
+        $crate::fuzz_afl_inner!($t);
+        $crate::impl_hash!($t);
+    };
     ($t:ty, $closure:expr) => {
         $crate::impl_input!($t);
         $crate::impl_converter!($t, $closure);
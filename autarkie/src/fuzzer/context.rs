@@ -1,4 +1,4 @@
-use crate::{FieldLocation, Id, Node, Visitor};
+use crate::{FieldLocation, Id, Node, ToProvenance, Visitor};
 use libafl::{corpus::CorpusId, inputs::ToTargetBytes, SerdeAny};
 use libafl_bolts::current_time;
 use libafl_bolts::AsSlice;
@@ -34,7 +34,7 @@ impl Context {
         converter: &mut TC,
         is_solution: bool,
     ) where
-        TC: ToTargetBytes<I>,
+        TC: ToTargetBytes<I> + ToProvenance<I>,
         I: Node,
     {
         let generated_fields = match &self.input_cause {
@@ -82,6 +82,16 @@ impl Context {
         if !std::fs::exists(&path).unwrap() {
             // warn that the same input gave new coverage == instability!
             std::fs::write(&path, rendered.as_slice()).unwrap();
+            // which node rendered which bytes, to explain the crash
+            if is_solution {
+                if let Some((_, provenance)) = converter.to_provenance(input) {
+                    std::fs::write(
+                        path.with_extension("provenance.json"),
+                        serde_json::to_string_pretty(&provenance).expect("____Xc4Rm7Lt"),
+                    )
+                    .unwrap();
+                }
+            }
         }
         self.input_cause = InputCause::Default;
     }
@@ -173,6 +183,20 @@ pub enum MutationMetadata {
     IterableMinimization,
     /// Iterable Pop
     IterablePop,
+    /// Iterable Reorder
+    IterableReorder,
+    /// Copy a node onto a same-typed node of the same input
+    CrossoverCopy,
+    /// Swap two same-typed nodes of the same input
+    CrossoverSwap,
+    /// Replace a node with a same-typed descendant
+    CrossoverHoist,
+    /// Wrap a recursive node inside a generated node of its type
+    CrossoverWrap,
+    /// Switch an enum's variant, keeping compatible fields
+    SwitchVariant,
+    /// Deterministic variant sweep of a new input
+    VariantSweep,
     /// Novelty Minimization
     NoveltyMinimization,
     /// Afl
@@ -185,5 +209,9 @@ pub enum MutationMetadata {
     CmplogBytes,
     /// I2S
     I2S,
+    /// Arithmetic
+    Arithmetic,
+    /// Byte Havoc
+    ByteHavoc,
     Random,
 }
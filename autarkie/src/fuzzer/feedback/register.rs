@@ -17,7 +17,7 @@ use libafl::{
 
 use crate::{
     fuzzer::{context::MutationMetadata, stages::stats::AutarkieStats},
-    Node, Visitor,
+    Node, ToProvenance, Visitor,
 };
 use libafl_bolts::Named;
 
@@ -32,7 +32,7 @@ pub struct RegisterFeedback<I, TC> {
 
 impl<I, TC> RegisterFeedback<I, TC>
 where
-    TC: ToTargetBytes<I> + Clone,
+    TC: ToTargetBytes<I> + ToProvenance<I> + Clone,
 {
     pub fn new(visitor: Rc<RefCell<Visitor>>, bytes_converter: TC, is_solution: bool) -> Self {
         Self {
@@ -47,7 +47,7 @@ where
 impl<I, TC, EM, OT, S> Feedback<EM, I, OT, S> for RegisterFeedback<I, TC>
 where
     I: Node,
-    TC: ToTargetBytes<I> + Clone,
+    TC: ToTargetBytes<I> + ToProvenance<I> + Clone,
     S: HasCurrentTestcase<I> + HasCorpus<I> + HasMetadata,
 {
     fn is_interesting(
@@ -1,6 +1,7 @@
 use super::context::{self, MutationMetadata};
 use super::feedback::register::RegisterFeedback;
 use super::mutators::iterable_pop::AutarkieIterablePopMutator;
+use super::mutators::iterable_reorder::AutarkieIterableReorderMutator;
 use super::mutators::recurse::AutarkieRecurseMutator;
 #[cfg(any(
     feature = "libfuzzer",
@@ -8,11 +9,19 @@ use super::mutators::recurse::AutarkieRecurseMutator;
     feature = "afl"
 ))]
 use super::stages::autarkie_cmp::AutarkieCmpLogStage;
+#[cfg(any(
+    feature = "libfuzzer",
+    feature = "llvm-fuzzer-no-link",
+    feature = "afl"
+))]
+use super::stages::auto_tokens::{merge_tokens, AutoTokensStage};
 use crate::fuzzer::context::Context;
 #[cfg(feature = "afl")]
 use crate::fuzzer::stages::cmp::CmpLogStage;
 use crate::fuzzer::stages::generate::generate;
-use crate::{DepthInfo, Visitor};
+#[cfg(feature = "afl")]
+use crate::fuzzer::stages::i2s::{AutarkieI2SStage, I2S_ROUNDS};
+use crate::{DepthInfo, ToProvenance, Visitor};
 use clap::Parser;
 use libafl::executors::forkserver::SHM_CMPLOG_ENV_VAR;
 use libafl::executors::StdChildArgs;
@@ -24,10 +33,14 @@ use libafl::{events::LlmpRestartingEventManager, mutators::I2SRandReplace};
 use libafl_bolts::StdTargetArgs;
 
 use crate::fuzzer::mutators::{
+    arithmetic::AutarkieArithmeticMutator,
+    byte_havoc::AutarkieByteHavocMutator,
+    crossover::AutarkieCrossoverMutator,
     generate_append::AutarkieGenerateAppendMutator,
     random::{AutarkieRandomMutator, RECURSE_STACK},
     splice::{AutarkieSpliceMutator, SPLICE_STACK},
     splice_append::{AutarkieSpliceAppendMutator, SPLICE_APPEND_STACK},
+    switch_variant::AutarkieSwitchVariantMutator,
 };
 use crate::fuzzer::stages::{
     binary_mutator::AutarkieBinaryMutatorStage,
@@ -38,6 +51,7 @@ use crate::fuzzer::stages::{
     novelty_minimization::NoveltyMinimizationStage,
     recursive_minimization::RecursiveMinimizationStage,
     stats::{AutarkieStats, StatsStage},
+    variant_sweep::VariantSweepStage,
 };
 use libafl::{
     corpus::{CachedOnDiskCorpus, Corpus, OnDiskCorpus},
@@ -54,6 +68,7 @@ use libafl::{
     monitors::MultiMonitor,
     mutators::{
         havoc_mutations, havoc_mutations_no_crossover, tokens_mutations, HavocScheduledMutator,
+        Tokens,
     },
     observers::{CanTrack, HitcountsMapObserver, StdMapObserver, TimeObserver},
     schedulers::{powersched::PowerSchedule, QueueScheduler, StdWeightedScheduler},
@@ -101,7 +116,11 @@ type AutarkieManager<F, I> = SimpleEventManager<I, SimpleMonitor<F>, AutarkieSta
 macro_rules! define_run_client {
     ($state: ident, $mgr: ident, $core: ident, $bytes_converter: ident, $opt: ident, $harness: ident, $body:block) => {
         #[cfg(not(feature = "fuzzbench"))]
-        pub fn run_client<I: Node + Input, TC: ToTargetBytes<I> + Clone, HF: Fn(&I) -> ExitKind>(
+        pub fn run_client<
+            I: Node + Input,
+            TC: ToTargetBytes<I> + ToProvenance<I> + Clone,
+            HF: Fn(&I) -> ExitKind,
+        >(
             $state: Option<AutarkieState<I>>,
             mut $mgr: AutarkieManager<I>,
             $core: ClientDescription,
@@ -116,7 +135,7 @@ macro_rules! define_run_client {
             F,
             HF: Fn(&I) -> ExitKind,
             I: Node + Input,
-            TC: ToTargetBytes<I> + Clone,
+            TC: ToTargetBytes<I> + ToProvenance<I> + Clone,
         >(
             $state: Option<AutarkieState<I>>,
             mut $mgr: AutarkieManager<F, I>,
@@ -215,8 +234,16 @@ define_run_client!(state, mgr, core, bytes_converter, opt, harness, {
         },
         opt.string_pool_size,
     );
+    visitor.set_interesting_probability(opt.interesting_probability);
     I::__autarkie_register(&mut visitor, None, 0);
     let recursive_nodes = visitor.calculate_recursion();
+    if let Some(tuning_file) = &opt.tuning_file {
+        let tuning = serde_json::from_str(&std::fs::read_to_string(tuning_file)?)
+            .map_err(|e| Error::illegal_argument(format!("invalid tuning file: {e}")))?;
+        visitor
+            .apply_tuning(tuning)
+            .map_err(|e| Error::illegal_argument(format!("invalid tuning file: {e}")))?;
+    }
     if is_main_node {
         std::fs::write(
             opt.output_dir.join("type_input_map.json"),
@@ -260,6 +287,10 @@ define_run_client!(state, mgr, core, bytes_converter, opt, harness, {
             novelty_minimization_stage,
             MinimizationStage::new(Rc::clone(&visitor), &map_feedback),
             RecursiveMinimizationStage::new(Rc::clone(&visitor), &map_feedback),
+            MutatingStageWrapper::new(
+                VariantSweepStage::new(Rc::clone(&visitor), opt.variant_sweep_budget),
+                Rc::clone(&visitor)
+            ),
         ),
     );
     let mut feedback = feedback_or!(
@@ -354,10 +385,15 @@ define_run_client!(state, mgr, core, bytes_converter, opt, harness, {
     #[cfg(any(feature = "libfuzzer", feature = "llvm-fuzzer-no-link"))]
     let tracing = ShadowTracingStage::new();
 
+    // Tokens are inserted into String and Vec<u8> fields by the byte havoc mutator
+    let mut tokens = Tokens::new();
     if let Some(dict_file) = &opt.dict_file {
         let file = std::fs::read_to_string(dict_file).expect("cannot read dict file");
         for entry in file.split("\n") {
             visitor.borrow_mut().register_string(entry.to_string());
+            if !entry.is_empty() {
+                tokens.add_token(&entry.as_bytes().to_vec());
+            }
         }
     }
 
@@ -373,9 +409,15 @@ define_run_client!(state, mgr, core, bytes_converter, opt, harness, {
         for string in strings.lines().into_iter() {
             if string_regex.is_match(string) {
                 visitor.borrow_mut().register_string(string.to_string());
+                tokens.add_token(&string.as_bytes().to_vec());
             }
         }
     }
+    // Reload the tokens harvested from comparisons by every core
+    for token in merge_tokens(&mut visitor.borrow_mut(), &opt.output_dir) {
+        tokens.add_token(&token);
+    }
+    state.add_metadata(tokens);
 
     // Reload corpus chunks if they exist
     for chunk_dir in std::fs::read_dir(fuzzer_dir.join("chunks"))? {
@@ -386,7 +428,10 @@ define_run_client!(state, mgr, core, bytes_converter, opt, harness, {
         }
     }
     state.add_metadata(context);
-    state.add_metadata(AutarkieStats::default());
+    // keep what we learned before a restart
+    if !state.has_metadata::<AutarkieStats>() {
+        state.add_metadata(AutarkieStats::default());
+    }
     let mut gen = vec![];
     // Reload corpus
     if state.must_load_initial_inputs() {
@@ -439,9 +484,7 @@ define_run_client!(state, mgr, core, bytes_converter, opt, harness, {
             .shmem_provider(&mut shmem_provider)
             .build(tuple_list!(cmplog_observer))
             .unwrap();
-        let tracing = CmpLogStage::new(Rc::clone(&visitor), cmplog_executor, cmplog_ref);
-        let cmplog = IfStage::new(cb, tuple_list!(tracing));
-        cmplog
+        CmpLogStage::new(Rc::clone(&visitor), cmplog_executor, cmplog_ref)
     };
 
     let cb = |_fuzzer: &mut _, _executor: &mut _, path: &Path| -> Result<I, Error> {
@@ -468,31 +511,84 @@ define_run_client!(state, mgr, core, bytes_converter, opt, harness, {
               _event_manager: &mut _|
      -> Result<bool, Error> { Ok(is_main_node) };
     let sync_stage = IfStage::new(cb, tuple_list!(sync_stage));
+    let cb = |_fuzzer: &mut _,
+              _executor: &mut _,
+              state: &mut StdState<CachedOnDiskCorpus<I>, I, StdRand, OnDiskCorpus<I>>,
+              _event_manager: &mut _|
+     -> Result<bool, Error> { Ok(opt.generate_stage) };
+    let generate_stage = IfStage::new(
+        cb,
+        tuple_list!(MutatingStageWrapper::new(
+            GenerateStage::new(Rc::clone(&visitor)),
+            Rc::clone(&visitor)
+        )),
+    );
     let splice_mutator = AutarkieSpliceMutator::new(Rc::clone(&visitor), opt.max_subslice_size);
     let random_mutator = AutarkieRandomMutator::new(Rc::clone(&visitor), opt.max_subslice_size);
     let splice_append_mutator = AutarkieSpliceAppendMutator::new(Rc::clone(&visitor));
+    let arithmetic_mutator = AutarkieArithmeticMutator::new(Rc::clone(&visitor));
+    let crossover_mutator = AutarkieCrossoverMutator::new(Rc::clone(&visitor));
+    let switch_variant_mutator = AutarkieSwitchVariantMutator::new(Rc::clone(&visitor));
+    let reorder_mutator =
+        AutarkieIterableReorderMutator::new(Rc::clone(&visitor), opt.max_subslice_size);
+    let byte_havoc_mutator = AutarkieByteHavocMutator::new(
+        havoc_mutations_no_crossover().merge(tokens_mutations()),
+        Rc::clone(&visitor),
+    );
     #[cfg(any(feature = "libfuzzer", feature = "llvm-fuzzer-no-link"))]
     let i2s = AutarkieBinaryMutatorStage::new(
         tuple_list!(I2SRandReplace::new()),
         7,
         MutationMetadata::I2S,
+        Rc::clone(&visitor),
+    );
+    #[cfg(feature = "afl")]
+    let i2s = AutarkieI2SStage::new(Rc::clone(&visitor), 7);
+    // tracing costs an extra execution of the cmplog binary, so we only trace (and run the stages
+    // using the comparisons) in the first rounds of a testcase. Unlike the in-process shadow
+    // tracing, which runs every round, see I2S_ROUNDS.
+    #[cfg(feature = "afl")]
+    let cb = |_fuzzer: &mut _,
+              _executor: &mut _,
+              state: &mut StdState<CachedOnDiskCorpus<I>, I, StdRand, OnDiskCorpus<I>>,
+              _event_manager: &mut _|
+     -> Result<bool, Error> {
+        Ok(state.current_testcase_mut()?.scheduled_count() < I2S_ROUNDS)
+    };
+    #[cfg(feature = "afl")]
+    let cmplog_stage = IfStage::new(
+        cb,
+        tuple_list!(
+            MutatingStageWrapper::new(cmplog, Rc::clone(&visitor)),
+            AutarkieCmpLogStage::new(Rc::clone(&visitor), bytes_converter.clone()),
+            AutoTokensStage::new(
+                Rc::clone(&visitor),
+                opt.output_dir.clone(),
+                fuzzer_dir.clone()
+            ),
+            MutatingStageWrapper::new(i2s, Rc::clone(&visitor)),
+        ),
     );
-    // TODO: I2S for AFL
     #[cfg(feature = "afl")]
     let mut stages = tuple_list!(
         minimization_stage,
-        MutatingStageWrapper::new(cmplog, Rc::clone(&visitor)),
-        AutarkieCmpLogStage::new(Rc::clone(&visitor)),
+        cmplog_stage,
         AutarkieMutationalStage::new(
             tuple_list!(
                 splice_append_mutator,
                 random_mutator,
                 splice_mutator,
+                arithmetic_mutator,
+                byte_havoc_mutator,
+                reorder_mutator,
+                crossover_mutator,
+                switch_variant_mutator,
                 AutarkieIterablePopMutator::new(Rc::clone(&visitor))
             ),
             opt.mutation_stack_size,
             Rc::clone(&visitor)
         ),
+        generate_stage,
         StatsStage::new(fuzzer_dir),
         sync_stage,
     );
@@ -500,18 +596,29 @@ define_run_client!(state, mgr, core, bytes_converter, opt, harness, {
     let mut stages = tuple_list!(
         minimization_stage,
         tracing,
-        AutarkieCmpLogStage::new(Rc::clone(&visitor)),
+        AutarkieCmpLogStage::new(Rc::clone(&visitor), bytes_converter.clone()),
+        AutoTokensStage::new(
+            Rc::clone(&visitor),
+            opt.output_dir.clone(),
+            fuzzer_dir.clone()
+        ),
         AutarkieMutationalStage::new(
             tuple_list!(
                 splice_append_mutator,
                 random_mutator,
                 splice_mutator,
+                arithmetic_mutator,
+                byte_havoc_mutator,
+                reorder_mutator,
+                crossover_mutator,
+                switch_variant_mutator,
                 AutarkieIterablePopMutator::new(Rc::clone(&visitor))
             ),
             SPLICE_STACK,
             Rc::clone(&visitor)
         ),
         MutatingStageWrapper::new(i2s, Rc::clone(&visitor)),
+        generate_stage,
         StatsStage::new(fuzzer_dir),
         sync_stage,
     );
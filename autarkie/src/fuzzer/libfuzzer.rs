@@ -5,6 +5,11 @@ macro_rules! fuzz_libfuzzer {
         $crate::impl_converter!($t);
         $crate::impl_hash!($t);
     };
+    ($t:ty, render = $render:expr) => {
+        $crate::impl_input!($t);
+        $crate::impl_converter!($t, render = $render);
+        $crate::impl_hash!($t);
+    };
     ($t:ty, $closure:expr) => {
         $crate::impl_input!($t);
         $crate::impl_converter!($t, $closure);
@@ -20,6 +25,12 @@ macro_rules! fuzz_libfuzzer_link {
         $crate::fuzz_libfuzzer_link_inner!($t);
         $crate::impl_hash!($t);
     };
+    ($t:ty, render = $render:expr) => {
+        $crate::impl_input!($t);
+        $crate::impl_converter!($t, render = $render);
+        $crate::fuzz_libfuzzer_link_inner!($t);
+        $crate::impl_hash!($t);
+    };
     ($t:ty, $closure:expr) => {
         $crate::impl_input!($t);
         $crate::impl_converter!($t, $closure);
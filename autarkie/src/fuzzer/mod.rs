@@ -9,7 +9,7 @@ pub mod mutators;
 mod stages;
 
 use crate::fuzzer::hooks::rare_share::RareShare;
-use crate::{Input, Node, ToTargetBytes};
+use crate::{Input, Node, ToProvenance, ToTargetBytes};
 use clap::Parser;
 use libafl::events::ClientDescription;
 use libafl::events::SimpleEventManager;
@@ -23,6 +23,7 @@ use libafl_bolts::tuples::tuple_list;
 use std::path::{Path, PathBuf};
 #[cfg(feature = "libfuzzer")]
 use std::{io::Write, str::FromStr};
+/// Run the fuzzer. The converter must also implement `ToProvenance`, see its docs for hand written ones.
 #[cfg(any(
     feature = "libfuzzer",
     feature = "afl",
@@ -31,7 +32,7 @@ use std::{io::Write, str::FromStr};
 pub fn run_fuzzer<I, TC, F>(bytes_converter: TC, harness: Option<F>)
 where
     I: Node + Input,
-    TC: ToTargetBytes<I> + Clone,
+    TC: ToTargetBytes<I> + ToProvenance<I> + Clone,
     F: Fn(&I) -> ExitKind,
 {
     use libafl::monitors::SimpleMonitor;
@@ -137,7 +138,7 @@ pub(crate) struct Opt {
     #[arg(short = 'i', default_value_t = 100)]
     initial_generated_inputs: usize,
 
-    /// Include a generate input stage (advanced)
+    /// Include a generate input stage, which generates more as mutations stall (advanced)
     #[arg(short = 'g')]
     generate_stage: bool,
 
@@ -175,15 +176,38 @@ pub(crate) struct Opt {
     #[arg(short = 'S')]
     get_strings: bool,
 
-    /// Amount of mutations per input
+    /// Probability of generating a number from its interesting values (eg: 0, MAX, NaN or cmplog constants)
+    #[arg(long, default_value_t = crate::DEFAULT_INTERESTING_PROBABILITY, value_parser = parse_probability)]
+    interesting_probability: f64,
+
+    /// Max amount of mutations per input, the actual amount is learned while fuzzing
     #[arg(long, default_value_t = 500)]
     mutation_stack_size: usize,
 
+    /// Max executions spent trying every other enum variant of a new input (0 to disable)
+    #[arg(long, default_value_t = 256)]
+    variant_sweep_budget: usize,
+
+    /// JSON file to reweight or disable enum variants, keyed by the names in type_input_map.json
+    /// eg: {"my_crate::Statement": {"disable": [3], "weights": {"0": 10}}}
+    #[arg(long)]
+    tuning_file: Option<PathBuf>,
+
     #[cfg(any(feature = "libfuzzer", feature = "llvm-fuzzer-no-link"))]
     #[arg(long)]
     run: Option<PathBuf>,
 }
 
+/// Parse a probability, rejecting values outside of [0, 1]
+fn parse_probability(arg: &str) -> Result<f64, String> {
+    let probability = arg.parse::<f64>().map_err(|e| e.to_string())?;
+    if (0.0..=1.0).contains(&probability) {
+        Ok(probability)
+    } else {
+        Err(format!("{probability} is not a probability in [0, 1]"))
+    }
+}
+
 #[macro_export]
 macro_rules! debug_grammar {
     ($t:ty) => {
@@ -0,0 +1,103 @@
+use crate::Visitor;
+use crate::{Arithmetic, Id, MutationType, Node};
+use libafl::{
+    corpus::Corpus,
+    mutators::{MutationResult, Mutator},
+    state::{HasCorpus, HasRand},
+    HasMetadata,
+};
+use libafl_bolts::Named;
+use std::collections::HashSet;
+use std::{borrow::Cow, cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use crate::fuzzer::context::Context;
+
+/// The largest delta we add or subtract
+pub const MAX_ARITHMETIC_DELTA: u8 = 35;
+
+/// Apply small changes (eg: +-delta, bit flips) to a number field in place
+pub struct AutarkieArithmeticMutator<I> {
+    visitor: Rc<RefCell<Visitor>>,
+    /// Ids of the number types we can mutate
+    numeric: HashSet<Id>,
+    phantom: PhantomData<I>,
+}
+
+impl<I, S> Mutator<I, S> for AutarkieArithmeticMutator<I>
+where
+    I: Node,
+    S: HasCorpus<I> + HasRand + HasMetadata,
+{
+    fn mutate(&mut self, state: &mut S, input: &mut I) -> Result<MutationResult, libafl::Error> {
+        let mut metadata = state.metadata_mut::<Context>()?;
+        input.__autarkie_fields(&mut self.visitor.borrow_mut(), 0);
+        let mut fields = self.visitor.borrow_mut().fields();
+        fields.retain(|field| {
+            field
+                .last()
+                .is_some_and(|(_, ty)| self.numeric.contains(ty))
+        });
+        if fields.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let mut visitor = self.visitor.borrow_mut();
+        let field = &fields[visitor.random_range(0, fields.len() - 1)];
+        let arithmetic = match visitor.random_range(0, 4) {
+            0 => Arithmetic::Add(visitor.random_range(1, MAX_ARITHMETIC_DELTA as usize) as u8),
+            1 => Arithmetic::Sub(visitor.random_range(1, MAX_ARITHMETIC_DELTA as usize) as u8),
+            2 => Arithmetic::FlipBit(visitor.random_range(0, 127) as u32),
+            3 => Arithmetic::SwapBytes,
+            _ => Arithmetic::Negate,
+        };
+        let path = VecDeque::from_iter(field.iter().map(|(i, ty)| i.0));
+        #[cfg(feature = "debug_mutators")]
+        println!("arithmetic | {:?} | {:?}", arithmetic, field);
+        input.__autarkie_mutate(
+            &mut MutationType::Arithmetic(arithmetic),
+            &mut visitor,
+            path,
+        );
+        metadata.add_mutation(crate::fuzzer::context::MutationMetadata::Arithmetic);
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut S,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl<I> Named for AutarkieArithmeticMutator<I> {
+    fn name(&self) -> &std::borrow::Cow<'static, str> {
+        &Cow::Borrowed("AutarkieArithmeticMutator")
+    }
+}
+
+impl<I> AutarkieArithmeticMutator<I> {
+    pub fn new(visitor: Rc<RefCell<Visitor>>) -> Self {
+        let mut numeric = HashSet::from_iter([
+            u8::__autarkie_id(),
+            u16::__autarkie_id(),
+            u32::__autarkie_id(),
+            u64::__autarkie_id(),
+            u128::__autarkie_id(),
+            i8::__autarkie_id(),
+            i16::__autarkie_id(),
+            i32::__autarkie_id(),
+            i64::__autarkie_id(),
+            i128::__autarkie_id(),
+            f32::__autarkie_id(),
+            f64::__autarkie_id(),
+        ]);
+        #[cfg(not(feature = "scale"))]
+        numeric.extend([usize::__autarkie_id(), isize::__autarkie_id()]);
+        Self {
+            visitor,
+            numeric,
+            phantom: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::Visitor;
+use crate::{Id, MutationType, Node};
+use libafl::{
+    corpus::Corpus,
+    mutators::{MutationResult, Mutator, MutatorsTuple},
+    state::{HasCorpus, HasRand},
+    HasMetadata,
+};
+use libafl_bolts::{rands::Rand, Named};
+use std::num::NonZero;
+use std::{borrow::Cow, cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use crate::fuzzer::context::Context;
+
+/// Max amount of stacked byte mutations
+pub const BYTE_HAVOC_STACK: usize = 16;
+
+/// Run byte mutations (eg: LibAFL's havoc) on the contents of a single String or Vec<u8> field.
+/// The reverse of `AutarkieBinaryMutatorStage`, which mutates the whole serialized input.
+pub struct AutarkieByteHavocMutator<I, M> {
+    inner: M,
+    visitor: Rc<RefCell<Visitor>>,
+    /// Ids of the types we mutate as bytes
+    leaves: [Id; 2],
+    phantom: PhantomData<I>,
+}
+
+impl<I, M, S> Mutator<I, S> for AutarkieByteHavocMutator<I, M>
+where
+    I: Node,
+    M: MutatorsTuple<Vec<u8>, S>,
+    S: HasCorpus<I> + HasRand + HasMetadata,
+{
+    fn mutate(&mut self, state: &mut S, input: &mut I) -> Result<MutationResult, libafl::Error> {
+        input.__autarkie_fields(&mut self.visitor.borrow_mut(), 0);
+        let mut fields = self.visitor.borrow_mut().fields();
+        fields.retain(|field| field.last().is_some_and(|(_, ty)| self.leaves.contains(ty)));
+        if fields.is_empty() || self.inner.len() == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let field_index = self.visitor.borrow_mut().random_range(0, fields.len() - 1);
+        let field = &fields[field_index];
+        let path = VecDeque::from_iter(field.iter().map(|(i, ty)| i.0));
+        #[cfg(feature = "debug_mutators")]
+        println!("byte_havoc | {:?}", field);
+        // take the field's contents, mutate them and put them back.
+        let mut bytes = vec![];
+        input.__autarkie_mutate(
+            &mut MutationType::Bytes(&mut bytes),
+            &mut self.visitor.borrow_mut(),
+            path.clone(),
+        );
+        let stack = state.rand_mut().between(1, BYTE_HAVOC_STACK);
+        for _ in 0..stack {
+            let mutation = state
+                .rand_mut()
+                .below(unsafe { NonZero::new(self.inner.len()).unwrap_unchecked() })
+                .into();
+            self.inner.get_and_mutate(mutation, state, &mut bytes)?;
+        }
+        input.__autarkie_mutate(
+            &mut MutationType::Bytes(&mut bytes),
+            &mut self.visitor.borrow_mut(),
+            path,
+        );
+        let mut metadata = state.metadata_mut::<Context>()?;
+        metadata.add_mutation(crate::fuzzer::context::MutationMetadata::ByteHavoc);
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut S,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl<I, M> Named for AutarkieByteHavocMutator<I, M> {
+    fn name(&self) -> &std::borrow::Cow<'static, str> {
+        &Cow::Borrowed("AutarkieByteHavocMutator")
+    }
+}
+
+impl<I, M> AutarkieByteHavocMutator<I, M> {
+    pub fn new(inner: M, visitor: Rc<RefCell<Visitor>>) -> Self {
+        Self {
+            inner,
+            visitor,
+            leaves: [String::__autarkie_id(), Vec::<u8>::__autarkie_id()],
+            phantom: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::Visitor;
+use crate::{MutationType, Node};
+use libafl::{
+    corpus::Corpus,
+    mutators::{MutationResult, Mutator},
+    state::{HasCorpus, HasRand},
+    HasMetadata,
+};
+use libafl_bolts::Named;
+use std::{borrow::Cow, cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use crate::fuzzer::context::{Context, MutationMetadata};
+
+/// How often we wrap a recursive node instead of crossing it over
+pub const WRAP_PROBABILITY: f64 = 0.2;
+/// How many wrappers we generate before giving up, as generation may pick non-recursive variants
+pub const WRAP_ATTEMPTS: usize = 5;
+
+/// Crossover between two nodes of the same type inside one input.
+/// Copies, swaps or hoists (replaces a node with a same-typed descendant) one onto the other,
+/// or wraps a recursive node inside a freshly generated node of its own type.
+/// Unlike `AutarkieSpliceMutator`, the donors come from the input itself.
+pub struct AutarkieCrossoverMutator<I> {
+    visitor: Rc<RefCell<Visitor>>,
+    phantom: PhantomData<I>,
+}
+
+impl<I, S> Mutator<I, S> for AutarkieCrossoverMutator<I>
+where
+    I: Node,
+    S: HasCorpus<I> + HasRand + HasMetadata,
+{
+    fn mutate(&mut self, state: &mut S, input: &mut I) -> Result<MutationResult, libafl::Error> {
+        let mut metadata = state.metadata_mut::<Context>()?;
+        let mut visitor = self.visitor.borrow_mut();
+        input.__autarkie_fields(&mut visitor, 0);
+        let fields = visitor.fields();
+        if fields.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let target = &fields[visitor.random_range(0, fields.len() - 1)];
+        let ((_, node_ty), id) = target.last().expect("Jm4xQe8T____");
+        let target_path = target.iter().map(|(i, _)| i.0).collect::<Vec<_>>();
+        if node_ty.is_recursive() && visitor.coinflip_with_prob(WRAP_PROBABILITY) {
+            #[cfg(feature = "debug_mutators")]
+            println!("crossover | wrap | {:?}", target);
+            if !wrap(input, &mut visitor, &target_path, *id) {
+                return Ok(MutationResult::Skipped);
+            }
+            metadata.add_mutation(MutationMetadata::CrossoverWrap);
+            return Ok(MutationResult::Mutated);
+        }
+        let donors = fields
+            .iter()
+            .filter(|field| field.last().is_some_and(|(_, ty)| ty == id))
+            .map(|field| field.iter().map(|(i, _)| i.0).collect::<Vec<_>>())
+            .filter(|path| *path != target_path)
+            .collect::<Vec<_>>();
+        if donors.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let donor_path = &donors[visitor.random_range(0, donors.len() - 1)];
+        let donor = copy(input, &mut visitor, donor_path);
+        if donor_path.starts_with(&target_path) {
+            #[cfg(feature = "debug_mutators")]
+            println!("crossover | hoist | {:?} | {:?}", target_path, donor_path);
+            splice(input, &mut visitor, &target_path, &donor);
+            metadata.add_mutation(MutationMetadata::CrossoverHoist);
+        } else if !target_path.starts_with(donor_path) && visitor.coinflip() {
+            #[cfg(feature = "debug_mutators")]
+            println!("crossover | swap | {:?} | {:?}", target_path, donor_path);
+            let original = copy(input, &mut visitor, &target_path);
+            splice(input, &mut visitor, &target_path, &donor);
+            // the nodes are disjoint, but maps and sets re-sort their items after a splice,
+            // so the donor may have moved
+            if let Some(donor_path) =
+                locate(input, &mut visitor, donor_path, &target_path, *id, &donor)
+            {
+                splice(input, &mut visitor, &donor_path, &original);
+            }
+            metadata.add_mutation(MutationMetadata::CrossoverSwap);
+        } else {
+            #[cfg(feature = "debug_mutators")]
+            println!("crossover | copy | {:?} | {:?}", target_path, donor_path);
+            splice(input, &mut visitor, &target_path, &donor);
+            metadata.add_mutation(MutationMetadata::CrossoverCopy);
+        }
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut S,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+/// Replace the node with a generated one of its type and put the original in a same-typed descendant.
+/// Returns false and leaves the input untouched if no generated node had such a descendant.
+fn wrap<I: Node>(input: &mut I, visitor: &mut Visitor, path: &[usize], id: crate::Id) -> bool {
+    let original = copy(input, visitor, path);
+    for _ in 0..WRAP_ATTEMPTS {
+        let bias = visitor.generate_depth();
+        input.__autarkie_mutate(
+            &mut MutationType::GenerateReplace(bias),
+            visitor,
+            VecDeque::from_iter(path.iter().copied()),
+        );
+        input.__autarkie_fields(visitor, 0);
+        let holes = visitor
+            .fields()
+            .into_iter()
+            .filter(|field| field.last().is_some_and(|(_, ty)| *ty == id))
+            .map(|field| field.iter().map(|(i, _)| i.0).collect::<Vec<_>>())
+            .filter(|hole| hole.len() > path.len() && hole.starts_with(path))
+            .collect::<Vec<_>>();
+        if !holes.is_empty() {
+            let hole = &holes[visitor.random_range(0, holes.len() - 1)];
+            splice(input, visitor, hole, &original);
+            return true;
+        }
+    }
+    splice(input, visitor, path, &original);
+    false
+}
+
+/// Find the node of type `id` serialized as `data`, preferring `path`.
+/// Otherwise `exclude` is never picked, as the target of the swap now holds the same data.
+fn locate<I: Node>(
+    input: &mut I,
+    visitor: &mut Visitor,
+    path: &[usize],
+    exclude: &[usize],
+    id: crate::Id,
+    data: &[u8],
+) -> Option<Vec<usize>> {
+    input.__autarkie_fields(visitor, 0);
+    let paths = visitor
+        .fields()
+        .into_iter()
+        .filter(|field| field.last().is_some_and(|(_, ty)| *ty == id))
+        .map(|field| field.iter().map(|(i, _)| i.0).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    if paths.iter().any(|other| other == path) && copy(input, visitor, path) == data {
+        return Some(path.to_vec());
+    }
+    paths
+        .into_iter()
+        .filter(|other| other != exclude)
+        .find(|other| copy(input, visitor, other) == data)
+}
+
+/// Serialize the node at the path
+fn copy<I: Node>(input: &mut I, visitor: &mut Visitor, path: &[usize]) -> Vec<u8> {
+    let mut buffer = vec![];
+    input.__autarkie_mutate(
+        &mut MutationType::Copy(&mut buffer),
+        visitor,
+        VecDeque::from_iter(path.iter().copied()),
+    );
+    buffer
+}
+
+fn splice<I: Node>(input: &mut I, visitor: &mut Visitor, path: &[usize], data: &[u8]) {
+    input.__autarkie_mutate(
+        &mut MutationType::Splice(&mut &data[..]),
+        visitor,
+        VecDeque::from_iter(path.iter().copied()),
+    );
+}
+
+impl<I> Named for AutarkieCrossoverMutator<I> {
+    fn name(&self) -> &std::borrow::Cow<'static, str> {
+        &Cow::Borrowed("AutarkieCrossoverMutator")
+    }
+}
+
+impl<I> AutarkieCrossoverMutator<I> {
+    pub fn new(visitor: Rc<RefCell<Visitor>>) -> Self {
+        Self {
+            visitor,
+            phantom: PhantomData,
+        }
+    }
+}
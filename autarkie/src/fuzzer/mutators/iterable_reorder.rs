@@ -0,0 +1,128 @@
+use crate::Visitor;
+use crate::{MutationType, Node, Reorder};
+use libafl::{
+    corpus::Corpus,
+    mutators::{MutationResult, Mutator},
+    state::{HasCorpus, HasRand},
+    HasMetadata,
+};
+use libafl_bolts::Named;
+use std::{borrow::Cow, cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use crate::fuzzer::context::Context;
+
+use super::commons::calculate_subslice_bounds;
+
+/// Reorder the elements of an iterable: swap, rotate, reverse, shuffle or duplicate.
+/// Fixed length iterables only get the reorderings which keep their length.
+/// Reorderings which change nothing (eg: of a map or a set) are skipped.
+pub struct AutarkieIterableReorderMutator<I> {
+    visitor: Rc<RefCell<Visitor>>,
+    max_subslice_size: usize,
+    phantom: PhantomData<I>,
+}
+
+impl<I, S> Mutator<I, S> for AutarkieIterableReorderMutator<I>
+where
+    I: Node,
+    S: HasCorpus<I> + HasRand + HasMetadata,
+{
+    fn mutate(&mut self, state: &mut S, input: &mut I) -> Result<MutationResult, libafl::Error> {
+        let mut metadata = state.metadata_mut::<Context>()?;
+        input.__autarkie_fields(&mut self.visitor.borrow_mut(), 0);
+        let mut fields = self.visitor.borrow_mut().fields();
+        fields.retain(|field| {
+            field
+                .last()
+                .is_some_and(|((_, node_ty), _)| matches!(node_ty, crate::NodeType::Iterable(_, len, _) if *len > 0))
+        });
+        if fields.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let mut visitor = self.visitor.borrow_mut();
+        let field = &fields[visitor.random_range(0, fields.len() - 1)];
+        let ((_, node_ty), _) = field.last().expect("Rv2sLk0P____");
+        let crate::NodeType::Iterable(is_fixed_len, len, _) = *node_ty else {
+            unreachable!("____cX7hNa3D");
+        };
+        // a single element can only be duplicated
+        let kind = if len == 1 {
+            4
+        } else if is_fixed_len {
+            visitor.random_range(0, 3)
+        } else {
+            visitor.random_range(0, 4)
+        };
+        let reorder = match kind {
+            0 => Reorder::Swap(
+                visitor.random_range(0, len - 1),
+                visitor.random_range(0, len - 1),
+            ),
+            1 => {
+                let range = calculate_subslice_bounds(len, self.max_subslice_size, &mut visitor);
+                let amount = visitor.random_range(0, range.len());
+                Reorder::Rotate(range, amount)
+            }
+            2 => Reorder::Reverse(calculate_subslice_bounds(
+                len,
+                self.max_subslice_size,
+                &mut visitor,
+            )),
+            3 => Reorder::Shuffle(calculate_subslice_bounds(
+                len,
+                self.max_subslice_size,
+                &mut visitor,
+            )),
+            _ => Reorder::Duplicate(visitor.random_range(0, len - 1)),
+        };
+        if is_fixed_len && !reorder.preserves_len() {
+            return Ok(MutationResult::Skipped);
+        }
+        let path = VecDeque::from_iter(field.iter().map(|(i, ty)| i.0));
+        #[cfg(feature = "debug_mutators")]
+        println!("iterable_reorder | {:?} | {:?}", reorder, field);
+        let mut before = vec![];
+        input.__autarkie_mutate(
+            &mut MutationType::Copy(&mut before),
+            &mut visitor,
+            path.clone(),
+        );
+        input.__autarkie_mutate(
+            &mut MutationType::Reorder(reorder),
+            &mut visitor,
+            path.clone(),
+        );
+        let mut after = vec![];
+        input.__autarkie_mutate(&mut MutationType::Copy(&mut after), &mut visitor, path);
+        // maps and sets are ordered by their items, so reordering them changes nothing
+        if before == after {
+            return Ok(MutationResult::Skipped);
+        }
+        metadata.add_mutation(crate::fuzzer::context::MutationMetadata::IterableReorder);
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut S,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl<I> Named for AutarkieIterableReorderMutator<I> {
+    fn name(&self) -> &std::borrow::Cow<'static, str> {
+        &Cow::Borrowed("AutarkieIterableReorderMutator")
+    }
+}
+
+impl<I> AutarkieIterableReorderMutator<I> {
+    pub fn new(visitor: Rc<RefCell<Visitor>>, max_subslice_size: usize) -> Self {
+        Self {
+            visitor,
+            max_subslice_size,
+            phantom: PhantomData,
+        }
+    }
+}
@@ -1,7 +1,12 @@
+pub mod arithmetic;
+pub mod byte_havoc;
 mod commons;
+pub mod crossover;
 pub mod generate_append;
 pub mod iterable_pop;
+pub mod iterable_reorder;
 pub mod random;
 pub mod recurse;
 pub mod splice;
 pub mod splice_append;
+pub mod switch_variant;
@@ -0,0 +1,71 @@
+use crate::Visitor;
+use crate::{MutationType, Node};
+use libafl::{
+    corpus::Corpus,
+    mutators::{MutationResult, Mutator},
+    state::{HasCorpus, HasRand},
+    HasMetadata,
+};
+use libafl_bolts::Named;
+use std::{borrow::Cow, cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use crate::fuzzer::context::Context;
+
+/// Switch an enum to another variant, keeping the fields whose type the new variant also has.
+/// eg: `Add(Box<Expr>, Box<Expr>)` becomes `Sub(..)` with the same operands.
+pub struct AutarkieSwitchVariantMutator<I> {
+    visitor: Rc<RefCell<Visitor>>,
+    phantom: PhantomData<I>,
+}
+
+impl<I, S> Mutator<I, S> for AutarkieSwitchVariantMutator<I>
+where
+    I: Node,
+    S: HasCorpus<I> + HasRand + HasMetadata,
+{
+    fn mutate(&mut self, state: &mut S, input: &mut I) -> Result<MutationResult, libafl::Error> {
+        let mut metadata = state.metadata_mut::<Context>()?;
+        let mut visitor = self.visitor.borrow_mut();
+        input.__autarkie_fields(&mut visitor, 0);
+        let enums = visitor.enums();
+        // reset the fields
+        visitor.fields();
+        if enums.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let (path, _, _) = &enums[visitor.random_range(0, enums.len() - 1)];
+        let bias = visitor.generate_depth();
+        #[cfg(feature = "debug_mutators")]
+        println!("switch_variant | {:?}", path);
+        input.__autarkie_mutate(
+            &mut MutationType::SwitchVariant(None, bias),
+            &mut visitor,
+            VecDeque::from_iter(path.iter().copied()),
+        );
+        metadata.add_mutation(crate::fuzzer::context::MutationMetadata::SwitchVariant);
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut S,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl<I> Named for AutarkieSwitchVariantMutator<I> {
+    fn name(&self) -> &std::borrow::Cow<'static, str> {
+        &Cow::Borrowed("AutarkieSwitchVariantMutator")
+    }
+}
+
+impl<I> AutarkieSwitchVariantMutator<I> {
+    pub fn new(visitor: Rc<RefCell<Visitor>>) -> Self {
+        Self {
+            visitor,
+            phantom: PhantomData,
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{fuzzer::context::MutationMetadata, MutationType, Node, Visitor};
+use crate::{
+    fuzzer::context::MutationMetadata, rendered_cmps, CmpOperands, FieldLocation, MutationType,
+    Node, ToProvenance, Visitor,
+};
 #[cfg(feature = "afl")]
 use libafl::observers::AflppCmpValuesMetadata;
 #[cfg(any(feature = "libfuzzer", feature = "llvm-fuzzer-no-link"))]
@@ -9,7 +12,7 @@ use libafl::{
     events::EventFirer,
     executors::{Executor, HasObservers},
     stages::{Restartable, Stage},
-    state::HasCurrentTestcase,
+    state::{HasCorpus, HasCurrentTestcase},
     Evaluator, HasMetadata,
 };
 use libafl_bolts::{
@@ -25,26 +28,31 @@ use std::{
 };
 
 use crate::fuzzer::context::Context;
+use crate::fuzzer::stages::stats::AutarkieStats;
 
 #[derive(Debug)]
-pub struct AutarkieCmpLogStage<I> {
+pub struct AutarkieCmpLogStage<I, TC> {
     visitor: Rc<RefCell<Visitor>>,
+    /// Renders the input with provenance, if the converter supports it
+    converter: TC,
     phantom: PhantomData<I>,
 }
 
-impl<I> AutarkieCmpLogStage<I> {
-    pub fn new(visitor: Rc<RefCell<Visitor>>) -> Self {
+impl<I, TC> AutarkieCmpLogStage<I, TC> {
+    pub fn new(visitor: Rc<RefCell<Visitor>>, converter: TC) -> Self {
         Self {
             visitor,
+            converter,
             phantom: PhantomData,
         }
     }
 }
 
-impl<E, EM, Z, S, I> Stage<E, EM, S, Z> for AutarkieCmpLogStage<I>
+impl<E, EM, Z, S, I, TC> Stage<E, EM, S, Z> for AutarkieCmpLogStage<I, TC>
 where
     I: Node + Serialize + Clone,
-    S: HasCurrentTestcase<I> + HasMetadata,
+    TC: ToProvenance<I>,
+    S: HasCurrentTestcase<I> + HasCorpus<I> + HasMetadata,
     E: Executor<EM, I, S, Z>,
     EM: EventFirer<I, S>,
     Z: Evaluator<E, EM, I, S>,
@@ -60,6 +68,11 @@ where
             return Ok(());
         }
         let mut unmutated_input = state.current_input_cloned()?;
+        // the target may compare the rendered input, eg: "1234" instead of the serialized integer
+        let rendered = self
+            .converter
+            .to_provenance(&unmutated_input)
+            .map(|rendered| (unmutated_input.clone(), rendered));
         let mut reduced = HashSet::new();
         let mut reduced_bytes = HashSet::new();
         #[cfg(feature = "afl")]
@@ -74,8 +87,8 @@ where
                         reduced.insert((left, right));
                     } else {
                         if let CmpValues::Bytes((left, right)) = i {
-                            reduced_bytes.insert(left.as_slice().to_vec());
-                            reduced_bytes.insert(right.as_slice().to_vec());
+                            reduced_bytes
+                                .insert((left.as_slice().to_vec(), right.as_slice().to_vec()));
                         }
                     }
                 }
@@ -91,56 +104,94 @@ where
                     reduced.insert((left, right));
                 } else {
                     if let CmpValues::Bytes((left, right)) = i {
-                        reduced_bytes.insert(left.as_slice().to_vec());
-                        reduced_bytes.insert(right.as_slice().to_vec());
+                        reduced_bytes.insert((left.as_slice().to_vec(), right.as_slice().to_vec()));
                     }
                 }
             }
         };
-        let mut unmutated_input_bytes = crate::serialize(&unmutated_input);
-        for cmp_chunk in reduced_bytes {
-            let mut start = None;
-            while let Some(index) = find_subsequence(&unmutated_input_bytes, &cmp_chunk, start) {
-                let mut cloned = unmutated_input_bytes.clone();
-                cloned.splice(index..index + cmp_chunk.len(), cmp_chunk.to_vec());
-                start = Some(index + cmp_chunk.len());
-                #[cfg(feature = "bincode")]
-                let Some(deserialized) = crate::maybe_deserialize(&cloned) else {
-                    continue;
-                };
-                #[cfg(not(feature = "bincode"))]
-                let Some(deserialized) = crate::maybe_deserialize(&mut cloned.as_slice()) else {
-                    continue;
-                };
-                unmutated_input_bytes = cloned;
-                state.metadata_mut::<Context>().unwrap().generated_input();
-                state
-                    .metadata_mut::<Context>()
-                    .unwrap()
-                    .add_mutation(MutationMetadata::CmplogBytes);
-                let res = fuzzer.evaluate_input(state, executor, manager, &deserialized)?;
-                state.metadata_mut::<Context>().unwrap().default_input();
-            }
+        // harvest the constants so we can also generate them
+        for (left, right) in &reduced {
+            let mut visitor = self.visitor.borrow_mut();
+            visitor.register_interesting(*left);
+            visitor.register_interesting(*right);
         }
-
-        for cmp in reduced {
+        // strings and byte arrays match structurally, so replacements of another length stay valid
+        let operands = reduced
+            .iter()
+            .map(|(left, right)| (CmpOperands::Int(*left, *right), MutationMetadata::Cmplog))
+            .chain(reduced_bytes.iter().map(|(left, right)| {
+                (
+                    CmpOperands::Bytes(left, right),
+                    MutationMetadata::CmplogBytes,
+                )
+            }))
+            .collect::<Vec<_>>();
+        for (cmp, mutation) in operands {
             unmutated_input.__autarkie_cmps(&mut self.visitor.borrow_mut(), 0, cmp);
-            let matches = self.visitor.borrow_mut().cmps();
-            for path in matches {
-                let cmp_path = path.0.iter().map(|(i, ty)| i.0).collect::<VecDeque<_>>();
-                let mut serialized_alternative = path.1.as_slice();
+            // whether the match was found through the provenance of the original input
+            let mut matches = self
+                .visitor
+                .borrow_mut()
+                .transformed_cmps()
+                .into_iter()
+                .map(|matched| (matched, false))
+                .collect::<Vec<_>>();
+            if let Some((original, (rendered, provenance))) = &rendered {
+                let rendered_matches = rendered_cmps(
+                    original,
+                    &mut self.visitor.borrow_mut(),
+                    rendered,
+                    provenance,
+                    cmp,
+                );
+                for matched in rendered_matches {
+                    if !matches
+                        .iter()
+                        .any(|(known, _)| known.1 == matched.1 && same_path(&known.0, &matched.0))
+                    {
+                        matches.push((matched, true));
+                    }
+                }
+            }
+            for ((path, alternative, transform), from_provenance) in matches {
+                let cmp_path = path.iter().map(|(i, ty)| i.0).collect::<VecDeque<_>>();
+                let mut serialized_alternative = alternative.as_slice();
                 state
                     .metadata_mut::<Context>()
                     .unwrap()
-                    .add_mutation(MutationMetadata::Cmplog);
+                    .add_mutation(mutation.clone());
                 #[cfg(debug_assertions)]
-                println!("cmplog_splice | one | {:?}", path.0);
-                unmutated_input.__autarkie_mutate(
+                println!("cmplog_splice | one | {:?} | {:?}", path, transform);
+                // provenance paths point into the original input, earlier replacements may have moved its nodes
+                let mut from_original;
+                let input = match &rendered {
+                    Some((original, _)) if from_provenance => {
+                        from_original = original.clone();
+                        &mut from_original
+                    }
+                    _ => &mut unmutated_input,
+                };
+                // keep a copy so we can revert replacements which violate the grammar's constraints
+                let backup = self
+                    .visitor
+                    .borrow()
+                    .has_constraints()
+                    .then(|| input.clone());
+                input.__autarkie_mutate(
                     &mut MutationType::Splice(&mut serialized_alternative),
                     &mut self.visitor.borrow_mut(),
                     cmp_path,
                 );
-                fuzzer.evaluate_input(state, executor, manager, &unmutated_input)?;
+                if self.visitor.borrow_mut().violates_constraints(&*input) {
+                    *input = backup.expect("Qm4rTz8v____");
+                    continue;
+                }
+                let corpus_count = state.corpus().count();
+                fuzzer.evaluate_input(state, executor, manager, &*input)?;
+                let found = state.corpus().count() > corpus_count;
+                state
+                    .metadata_mut::<AutarkieStats>()?
+                    .add_cmp_transform(transform, found);
             }
         }
 
@@ -148,7 +199,13 @@ where
     }
 }
 
-impl<I, S> Restartable<S> for AutarkieCmpLogStage<I> {
+fn same_path(left: &[FieldLocation], right: &[FieldLocation]) -> bool {
+    left.iter()
+        .map(|(i, _)| i.0)
+        .eq(right.iter().map(|(i, _)| i.0))
+}
+
+impl<I, TC, S> Restartable<S> for AutarkieCmpLogStage<I, TC> {
     fn should_restart(&mut self, state: &mut S) -> Result<bool, libafl::Error> {
         Ok(true)
     }
@@ -157,9 +214,3 @@ impl<I, S> Restartable<S> for AutarkieCmpLogStage<I> {
         Ok(())
     }
 }
-
-fn find_subsequence(haystack: &[u8], needle: &[u8], start: Option<usize>) -> Option<usize> {
-    haystack[start.unwrap_or(0)..]
-        .windows(needle.len())
-        .position(|window| window == needle)
-}
@@ -0,0 +1,155 @@
+use crate::{Node, TokenPool, Visitor};
+#[cfg(feature = "afl")]
+use libafl::observers::AflppCmpValuesMetadata;
+#[cfg(any(feature = "libfuzzer", feature = "llvm-fuzzer-no-link"))]
+use libafl::observers::CmpValuesMetadata;
+use libafl::{
+    events::EventFirer,
+    executors::Executor,
+    mutators::Tokens,
+    observers::CmpValues,
+    stages::{Restartable, Stage},
+    state::HasCurrentTestcase,
+    Evaluator, HasMetadata,
+};
+use libafl_bolts::AsSlice;
+use serde::Serialize;
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// The file each core persists its harvested tokens to
+pub const AUTO_TOKENS_FILE: &str = "auto_tokens.json";
+
+/// Harvests the byte operands of the comparisons traced on the current input into the visitor's `TokenPool`.
+/// New tokens are also added to the `Tokens` metadata used by the byte havoc mutator.
+/// Periodically persists the pool and merges the pools of the other cores.
+#[derive(Debug)]
+pub struct AutoTokensStage<I> {
+    visitor: Rc<RefCell<Visitor>>,
+    /// The output dir, containing a directory per core
+    output_dir: PathBuf,
+    fuzzer_dir: PathBuf,
+    last_sync: Instant,
+    /// Whether our pool changed since we last persisted it
+    changed: bool,
+    phantom: PhantomData<I>,
+}
+
+impl<I> AutoTokensStage<I> {
+    pub fn new(visitor: Rc<RefCell<Visitor>>, output_dir: PathBuf, fuzzer_dir: PathBuf) -> Self {
+        Self {
+            visitor,
+            output_dir,
+            fuzzer_dir,
+            last_sync: Instant::now(),
+            changed: false,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E, EM, Z, S, I> Stage<E, EM, S, Z> for AutoTokensStage<I>
+where
+    I: Node + Serialize,
+    S: HasCurrentTestcase<I> + HasMetadata,
+    E: Executor<EM, I, S, Z>,
+    EM: EventFirer<I, S>,
+    Z: Evaluator<E, EM, I, S>,
+{
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+    ) -> Result<(), libafl::Error> {
+        let mut operands = vec![];
+        #[cfg(feature = "afl")]
+        if let Ok(data) = state.metadata::<AflppCmpValuesMetadata>() {
+            for cmp in data.orig_cmpvals().values().flatten() {
+                if let CmpValues::Bytes((left, right)) = cmp {
+                    operands.push(left.as_slice().to_vec());
+                    operands.push(right.as_slice().to_vec());
+                }
+            }
+        }
+        #[cfg(any(feature = "libfuzzer", feature = "llvm-fuzzer-no-link"))]
+        if let Ok(data) = state.metadata::<CmpValuesMetadata>() {
+            for cmp in data.list.iter() {
+                if let CmpValues::Bytes((left, right)) = cmp {
+                    operands.push(left.as_slice().to_vec());
+                    operands.push(right.as_slice().to_vec());
+                }
+            }
+        }
+        let mut new_tokens = vec![];
+        for operand in operands {
+            if self.visitor.borrow_mut().register_token(&operand) {
+                new_tokens.push(operand);
+                self.changed = true;
+            }
+        }
+        if self.last_sync.elapsed() > Duration::from_secs(AUTO_TOKENS_SYNC_SECS) {
+            new_tokens.extend(merge_tokens(
+                &mut self.visitor.borrow_mut(),
+                &self.output_dir,
+            ));
+            if self.changed {
+                save_tokens(self.visitor.borrow().tokens(), &self.fuzzer_dir);
+                self.changed = false;
+            }
+            self.last_sync = Instant::now();
+        }
+        if !new_tokens.is_empty() {
+            let tokens = state.metadata_mut::<Tokens>()?;
+            for token in &new_tokens {
+                tokens.add_token(token);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How often we persist our tokens and merge the other cores' tokens
+const AUTO_TOKENS_SYNC_SECS: u64 = 30;
+
+/// Merge the persisted tokens of every core (including ours, from a previous run), returns the new ones
+pub fn merge_tokens(visitor: &mut Visitor, output_dir: &Path) -> Vec<Vec<u8>> {
+    let mut new_tokens = vec![];
+    let Ok(dirs) = std::fs::read_dir(output_dir) else {
+        return new_tokens;
+    };
+    for dir in dirs.filter_map(Result::ok) {
+        let Ok(data) = std::fs::read(dir.path().join(AUTO_TOKENS_FILE)) else {
+            continue;
+        };
+        // skip pools we cannot parse, eg: of an older version
+        let Ok(pool) = serde_json::from_slice::<TokenPool>(&data) else {
+            continue;
+        };
+        new_tokens.extend(visitor.merge_tokens(&pool));
+    }
+    new_tokens
+}
+
+fn save_tokens(pool: &TokenPool, fuzzer_dir: &Path) {
+    // write and rename, so other cores never read a partial file
+    let tmp = fuzzer_dir.join(format!(".{AUTO_TOKENS_FILE}"));
+    std::fs::write(&tmp, serde_json::to_vec(pool).expect("Jd3nWq8x____")).expect("u0GZp2Lc____");
+    std::fs::rename(tmp, fuzzer_dir.join(AUTO_TOKENS_FILE)).expect("Xb7tRk1e____");
+}
+
+impl<I, S> Restartable<S> for AutoTokensStage<I> {
+    fn should_restart(&mut self, state: &mut S) -> Result<bool, libafl::Error> {
+        Ok(true)
+    }
+
+    fn clear_progress(&mut self, state: &mut S) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
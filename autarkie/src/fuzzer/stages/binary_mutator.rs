@@ -26,16 +26,23 @@ pub struct AutarkieBinaryMutatorStage<S, M, I> {
     inner: M,
     stack: usize,
     mutation_ty: MutationMetadata,
+    visitor: Rc<RefCell<Visitor>>,
     phantom: PhantomData<(I, S)>,
 }
 
 impl<S, M, I> AutarkieBinaryMutatorStage<S, M, I> {
     /// Create a `AutarkieBinaryMutatorStage`
-    pub fn new(inner: M, stack: usize, mutation_ty: MutationMetadata) -> Self {
+    pub fn new(
+        inner: M,
+        stack: usize,
+        mutation_ty: MutationMetadata,
+        visitor: Rc<RefCell<Visitor>>,
+    ) -> Self {
         Self {
             stack,
             inner,
             mutation_ty,
+            visitor,
             phantom: PhantomData,
         }
     }
@@ -70,6 +77,13 @@ where
             let Some(deserialized) = crate::maybe_deserialize(&mut input.as_slice()) else {
                 return Ok(());
             };
+            if self
+                .visitor
+                .borrow_mut()
+                .violates_constraints(&deserialized)
+            {
+                continue;
+            }
             let mut metadata = state.metadata_mut::<Context>().expect("oBusH4xj____");
             metadata.add_mutation(self.mutation_ty.clone());
             fuzzer.evaluate_input(state, executor, manager, &deserialized)?;
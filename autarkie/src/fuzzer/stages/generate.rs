@@ -1,4 +1,7 @@
-use crate::{fuzzer::context::Context, Node, Visitor};
+use crate::{
+    fuzzer::{context::Context, stages::stats::AutarkieStats},
+    Node, Visitor,
+};
 use libafl::{
     corpus::Corpus,
     events::EventFirer,
@@ -8,11 +11,23 @@ use libafl::{
     Evaluator, HasMetadata,
 };
 use serde::Serialize;
-use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+use std::{cell::RefCell, marker::PhantomData, rc::Rc, time::Instant};
+
+/// Lowest amount of inputs generated per round, while mutations keep finding new coverage
+pub const GENERATE_MIN_RATIO: f64 = 0.01;
+/// Highest amount of inputs generated per round, approached as mutations stall
+pub const GENERATE_MAX_RATIO: f64 = 50.0;
+/// Seconds without new coverage from mutations after which we generate half of the max
+pub const GENERATE_STALL_SECS: f64 = 300.0;
 
+/// Generate inputs from scratch.
+/// The amount per round rises with the time since mutations last found a new corpus entry.
 #[derive(Debug)]
 pub struct GenerateStage<I> {
     visitor: Rc<RefCell<Visitor>>,
+    /// Corpus size after our last round, so we only count what the other stages found
+    corpus_count: usize,
+    last_find: Instant,
     phantom: PhantomData<I>,
 }
 
@@ -20,6 +35,8 @@ impl<I> GenerateStage<I> {
     pub fn new(visitor: Rc<RefCell<Visitor>>) -> Self {
         Self {
             visitor,
+            corpus_count: 0,
+            last_find: Instant::now(),
             phantom: PhantomData,
         }
     }
@@ -40,16 +57,30 @@ where
         state: &mut S,
         manager: &mut EM,
     ) -> Result<(), libafl::Error> {
-        let mut metadata = state.metadata_mut::<Context>()?;
-        metadata.generated_input();
-        let Some(generated) = generate(&mut self.visitor.borrow_mut()) else {
+        if state.corpus().count() > self.corpus_count {
+            self.last_find = Instant::now();
+        }
+        let stall = self.last_find.elapsed().as_secs_f64();
+        let ratio = GENERATE_MIN_RATIO
+            + (GENERATE_MAX_RATIO - GENERATE_MIN_RATIO) * stall / (stall + GENERATE_STALL_SECS);
+        state.metadata_mut::<AutarkieStats>()?.generate_ratio = ratio;
+        let amount =
+            ratio as usize + self.visitor.borrow_mut().coinflip_with_prob(ratio.fract()) as usize;
+        for _ in 0..amount {
+            let Some(generated) = generate(&mut self.visitor.borrow_mut()) else {
+                continue;
+            };
+            // the whole input is new, so the feedback registers all of its chunks
+            let _ = self.visitor.borrow_mut().serialized();
+            let mut metadata = state.metadata_mut::<Context>()?;
+            metadata.generated_input();
+            metadata.add_mutation(crate::fuzzer::context::MutationMetadata::Generate);
+            fuzzer.evaluate_input(state, executor, manager, &generated)?;
+            let mut metadata = state.metadata_mut::<Context>()?;
             metadata.default_input();
-            return Ok(());
-        };
-        metadata.add_mutation(crate::fuzzer::context::MutationMetadata::Generate);
-        fuzzer.evaluate_input(state, executor, manager, &generated)?;
-        let mut metadata = state.metadata_mut::<Context>()?;
-        metadata.default_input();
+            let _ = metadata.clear_mutations();
+        }
+        self.corpus_count = state.corpus().count();
         Ok(())
     }
 }
@@ -0,0 +1,143 @@
+use crate::{
+    fuzzer::context::MutationMetadata, CmpOperands, CmpTransform, MutationType, Node, Visitor,
+};
+use libafl::observers::{AflppCmpValuesMetadata, CmpValues};
+use libafl::{
+    corpus::Corpus,
+    events::EventFirer,
+    executors::Executor,
+    stages::{Restartable, Stage},
+    state::{HasCorpus, HasCurrentTestcase},
+    Evaluator, HasMetadata,
+};
+use libafl_bolts::AsSlice;
+use serde::Serialize;
+use std::{cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use crate::fuzzer::context::Context;
+use crate::fuzzer::stages::stats::AutarkieStats;
+
+/// How many rounds of a testcase we trace with cmplog and run the I2S stage in.
+/// Every traced round costs an extra execution of the cmplog binary, through its own forkserver
+/// and with the slower cmplog instrumentation. In-process, the shadow executor traces in the
+/// harness' own process for about the cost of a normal execution, so it traces and runs I2S every
+/// round. Like AFL++, which runs its cmplog stage once per queue entry, we only trace the first
+/// rounds of a testcase.
+pub const I2S_ROUNDS: usize = 8;
+
+/// Input-to-state replacement for the AFL++ forkserver, the counterpart of `I2SRandReplace`.
+/// Picks a comparison the target made on the current input and replaces one operand with the other.
+/// The operands are matched against the input's nodes, so the replacement keeps the grammar.
+/// Needs the cmplog tracing to run on the current input first.
+#[derive(Debug)]
+pub struct AutarkieI2SStage<I> {
+    visitor: Rc<RefCell<Visitor>>,
+    stack: usize,
+    phantom: PhantomData<I>,
+}
+
+impl<I> AutarkieI2SStage<I> {
+    pub fn new(visitor: Rc<RefCell<Visitor>>, stack: usize) -> Self {
+        Self {
+            visitor,
+            stack,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E, EM, Z, S, I> Stage<E, EM, S, Z> for AutarkieI2SStage<I>
+where
+    I: Node + Serialize + Clone,
+    S: HasCurrentTestcase<I> + HasCorpus<I> + HasMetadata,
+    E: Executor<EM, I, S, Z>,
+    EM: EventFirer<I, S>,
+    Z: Evaluator<E, EM, I, S>,
+{
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+    ) -> Result<(), libafl::Error> {
+        let input = state.current_input_cloned()?;
+        let cmps = {
+            let Ok(data) = state.metadata::<AflppCmpValuesMetadata>() else {
+                return Ok(());
+            };
+            data.orig_cmpvals()
+                .values()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        if cmps.is_empty() {
+            return Ok(());
+        }
+        for _ in 0..self.stack {
+            let cmp = {
+                let mut visitor = self.visitor.borrow_mut();
+                &cmps[visitor.random_range(0, cmps.len() - 1)]
+            };
+            let Some((replaced, transform)) = replace(&input, cmp, &mut self.visitor.borrow_mut())
+            else {
+                continue;
+            };
+            if self.visitor.borrow_mut().violates_constraints(&replaced) {
+                let _ = self.visitor.borrow_mut().serialized();
+                continue;
+            }
+            state
+                .metadata_mut::<Context>()?
+                .add_mutation(MutationMetadata::I2S);
+            let corpus_count = state.corpus().count();
+            fuzzer.evaluate_input(state, executor, manager, &replaced)?;
+            let found = state.corpus().count() > corpus_count;
+            state
+                .metadata_mut::<AutarkieStats>()?
+                .add_cmp_transform(transform, found);
+            let _ = self.visitor.borrow_mut().serialized();
+        }
+        Ok(())
+    }
+}
+
+/// Replace one operand of the comparison with the other in a random matching node
+fn replace<I>(input: &I, cmp: &CmpValues, visitor: &mut Visitor) -> Option<(I, CmpTransform)>
+where
+    I: Node + Clone,
+{
+    let operands = if let Some((left, right, _is_const)) = cmp.to_u64_tuple() {
+        CmpOperands::Int(left, right)
+    } else if let CmpValues::Bytes((left, right)) = cmp {
+        CmpOperands::Bytes(left.as_slice(), right.as_slice())
+    } else {
+        return None;
+    };
+    input.__autarkie_cmps(visitor, 0, operands);
+    let matches = visitor.transformed_cmps();
+    if matches.is_empty() {
+        return None;
+    }
+    let (path, alternative, transform) = &matches[visitor.random_range(0, matches.len() - 1)];
+    let mut replaced = input.clone();
+    #[cfg(feature = "debug_mutators")]
+    println!("i2s | {:?} | {:?}", path, transform);
+    replaced.__autarkie_mutate(
+        &mut MutationType::Splice(&mut alternative.as_slice()),
+        visitor,
+        path.iter().map(|(i, _)| i.0).collect::<VecDeque<_>>(),
+    );
+    Some((replaced, *transform))
+}
+
+impl<I, S> Restartable<S> for AutarkieI2SStage<I> {
+    fn should_restart(&mut self, state: &mut S) -> Result<bool, libafl::Error> {
+        Ok(true)
+    }
+
+    fn clear_progress(&mut self, state: &mut S) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
@@ -4,13 +4,23 @@
     feature = "afl"
 ))]
 pub mod autarkie_cmp;
+#[cfg(any(
+    feature = "libfuzzer",
+    feature = "llvm-fuzzer-no-link",
+    feature = "afl"
+))]
+pub mod auto_tokens;
 pub mod binary_mutator;
 #[cfg(feature = "afl")]
 pub mod cmp;
 pub mod generate;
+#[cfg(feature = "afl")]
+pub mod i2s;
 pub mod minimization;
 pub mod mutating;
 pub mod mutational;
 pub mod novelty_minimization;
 pub mod recursive_minimization;
+pub mod scheduler;
 pub mod stats;
+pub mod variant_sweep;
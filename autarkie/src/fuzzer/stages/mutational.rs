@@ -1,12 +1,13 @@
 //! Stage that wraps mutating stages for stats and cleanup
 use crate::fuzzer::context::Context;
-use crate::Visitor;
+use crate::fuzzer::stages::stats::AutarkieStats;
+use crate::{Node, Visitor};
 use core::{marker::PhantomData, time::Duration};
-use libafl::state::HasRand;
-use libafl_bolts::current_time;
-use libafl_bolts::rands::Rand;
+use libafl::corpus::Corpus;
+use libafl::state::{HasCorpus, HasRand};
+use libafl_bolts::{current_time, tuples::NamedTuple};
+use std::cell::RefCell;
 use std::rc::Rc;
-use std::{cell::RefCell, num::NonZero};
 
 use libafl::{
     events::EventFirer,
@@ -17,9 +18,13 @@ use libafl::{
     Error, Evaluator, HasMetadata,
 };
 
+/// Stack mutations on the current testcase, evaluating after each one.
+/// The mutator and the stack size (up to `stack`) are picked by weights learned online
+/// from how often each one found new corpus entries, see `MutatorScheduler`.
 #[derive(Debug)]
 pub struct AutarkieMutationalStage<S, M, I> {
     inner: M,
+    /// Max amount of mutations per testcase
     stack: usize,
     visitor: Rc<RefCell<Visitor>>,
     phantom: PhantomData<(I, S)>,
@@ -39,11 +44,12 @@ impl<S, M, I> AutarkieMutationalStage<S, M, I> {
 
 impl<E, EM, M, Z, S, I> Stage<E, EM, S, Z> for AutarkieMutationalStage<S, M, I>
 where
+    I: Node + Clone,
     E: Executor<EM, I, S, Z>,
     Z: Evaluator<E, EM, I, S>,
     EM: EventFirer<I, S>,
-    S: HasMetadata + HasCurrentTestcase<I> + HasRand,
-    M: MutatorsTuple<I, S>,
+    S: HasMetadata + HasCurrentTestcase<I> + HasCorpus<I> + HasRand,
+    M: MutatorsTuple<I, S> + NamedTuple,
 {
     fn perform(
         &mut self,
@@ -53,16 +59,47 @@ where
         manager: &mut EM,
     ) -> Result<(), Error> {
         let mut current = state.current_input_cloned().unwrap();
-        for i in 0..self.stack {
+        let names = self.inner.names();
+        let names = names.iter().map(|name| name.as_ref()).collect::<Vec<_>>();
+        let scheduler = &mut state.metadata_mut::<AutarkieStats>()?.scheduler;
+        scheduler.init(&names, self.stack);
+        let (stack_idx, stack) = scheduler.pick_stack(self.visitor.borrow_mut().random_float());
+        let mut executions = 0;
+        let mut finds = 0;
+        for i in 0..stack {
             let idx = state
-                .rand_mut()
-                .below(unsafe { NonZero::new(self.inner.len()).unwrap_unchecked() })
-                .into();
-            if self.inner.get_and_mutate(idx, state, &mut current)? == MutationResult::Mutated {
-                fuzzer.evaluate_input(state, executor, manager, &current)?;
+                .metadata::<AutarkieStats>()?
+                .scheduler
+                .pick_mutator(self.visitor.borrow_mut().random_float());
+            // keep a copy so we can revert mutations which violate the grammar's constraints
+            let backup = self
+                .visitor
+                .borrow()
+                .has_constraints()
+                .then(|| current.clone());
+            if self.inner.get_and_mutate(idx.into(), state, &mut current)?
+                == MutationResult::Mutated
+            {
+                if self.visitor.borrow_mut().violates_constraints(&current) {
+                    current = backup.expect("ZP3bLq7d____");
+                } else {
+                    let corpus_count = state.corpus().count();
+                    fuzzer.evaluate_input(state, executor, manager, &current)?;
+                    let found = state.corpus().count() > corpus_count;
+                    executions += 1;
+                    finds += found as u64;
+                    state
+                        .metadata_mut::<AutarkieStats>()?
+                        .scheduler
+                        .record_mutator(idx, found);
+                }
             }
             let _ = self.visitor.borrow_mut().serialized();
         }
+        state
+            .metadata_mut::<AutarkieStats>()?
+            .scheduler
+            .record_stack(stack_idx, executions, finds);
         Ok(())
     }
 }
@@ -0,0 +1,223 @@
+//! Online (MOpt-style) weighting of the mutators and the stack size of `AutarkieMutationalStage`
+use serde::{Deserialize, Serialize};
+
+/// How many recorded executions before the weights are recomputed
+pub const SCHEDULER_PERIOD: u64 = 5000;
+/// Share of the probability mass spread uniformly, so bad arms are still tried now and then
+pub const SCHEDULER_EXPLORATION: f64 = 0.1;
+
+/// How many executions the overall find rate counts for when smoothing an arm's rate
+pub const SCHEDULER_PRIOR_USES: f64 = 100.0;
+
+/// Learned weights of the mutators and the stack sizes of one client.
+/// Lives in `AutarkieStats` so it survives restarts and ends up in `stats.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MutatorScheduler {
+    /// Indexed like the mutators tuple of the stage
+    mutators: Vec<Arm>,
+    /// From the configured stack size halving down to 1
+    stacks: Vec<Arm>,
+    /// Executions recorded since the weights were last recomputed
+    pending: u64,
+}
+
+/// A mutator or stack size and how well it did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Arm {
+    name: String,
+    /// Probability of being picked
+    weight: f64,
+    /// Executions spent on it (decays every period)
+    uses: u64,
+    /// New corpus entries found with it (decays every period)
+    finds: u64,
+}
+
+impl Arm {
+    fn new(name: String, weight: f64) -> Self {
+        Self {
+            name,
+            weight,
+            uses: 0,
+            finds: 0,
+        }
+    }
+}
+
+impl MutatorScheduler {
+    /// (Re)start learning if the mutators or the stack size differ from the ones we learned with
+    pub fn init(&mut self, names: &[&str], max_stack: usize) {
+        let stacks = stack_sizes(max_stack);
+        if self.mutators.len() == names.len()
+            && self
+                .mutators
+                .iter()
+                .zip(names)
+                .all(|(arm, name)| arm.name == *name)
+            && self.stacks.len() == stacks.len()
+            && self
+                .stacks
+                .iter()
+                .zip(&stacks)
+                .all(|(arm, stack)| arm.name == stack.to_string())
+        {
+            return;
+        }
+        self.mutators = names
+            .iter()
+            .map(|name| Arm::new(name.to_string(), 1.0 / names.len() as f64))
+            .collect();
+        self.stacks = stacks
+            .iter()
+            .map(|stack| Arm::new(stack.to_string(), 1.0 / stacks.len() as f64))
+            .collect();
+        self.pending = 0;
+    }
+
+    /// Pick a mutator index given a random number in [0, 1)
+    pub fn pick_mutator(&self, random: f64) -> usize {
+        pick(&self.mutators, random)
+    }
+
+    /// Pick a stack size given a random number in [0, 1). Returns the index and the size.
+    pub fn pick_stack(&self, random: f64) -> (usize, usize) {
+        let idx = pick(&self.stacks, random);
+        (idx, self.stacks[idx].name.parse().expect("____Vw3nHs8Q"))
+    }
+
+    /// Record one execution of the mutator and whether it added a new corpus entry
+    pub fn record_mutator(&mut self, idx: usize, found: bool) {
+        let arm = &mut self.mutators[idx];
+        arm.uses += 1;
+        arm.finds += found as u64;
+        self.pending += 1;
+        if self.pending >= SCHEDULER_PERIOD {
+            self.update();
+        }
+    }
+
+    /// Record a whole stack: how many executions it took and how many new corpus entries it found
+    pub fn record_stack(&mut self, idx: usize, executions: u64, finds: u64) {
+        let arm = &mut self.stacks[idx];
+        arm.uses += executions;
+        arm.finds += finds;
+    }
+
+    /// Recompute the weights from the smoothed find rates, then decay the counts
+    /// so the weights follow the campaign as it progresses.
+    fn update(&mut self) {
+        reweight(&mut self.mutators);
+        reweight(&mut self.stacks);
+        self.pending = 0;
+    }
+}
+
+/// The configured stack size halving down to 1
+fn stack_sizes(max_stack: usize) -> Vec<usize> {
+    let mut stacks = vec![];
+    let mut stack = max_stack.max(1);
+    loop {
+        stacks.push(stack);
+        if stack == 1 {
+            return stacks;
+        }
+        stack /= 2;
+    }
+}
+
+fn pick(arms: &[Arm], random: f64) -> usize {
+    let total = arms.iter().map(|arm| arm.weight).sum::<f64>();
+    let mut target = random * total;
+    for (idx, arm) in arms.iter().enumerate() {
+        if target < arm.weight {
+            return idx;
+        }
+        target -= arm.weight;
+    }
+    arms.len() - 1
+}
+
+fn reweight(arms: &mut [Arm]) {
+    // shrink towards the overall find rate, so rarely used arms are neither favoured nor starved
+    let uses = arms.iter().map(|arm| arm.uses).sum::<u64>();
+    let finds = arms.iter().map(|arm| arm.finds).sum::<u64>();
+    let prior = (finds as f64 + 1.0) / (uses as f64 + 1.0);
+    let rates = arms
+        .iter()
+        .map(|arm| {
+            (arm.finds as f64 + prior * SCHEDULER_PRIOR_USES)
+                / (arm.uses as f64 + SCHEDULER_PRIOR_USES)
+        })
+        .collect::<Vec<_>>();
+    let total = rates.iter().sum::<f64>();
+    let uniform = 1.0 / arms.len() as f64;
+    for (arm, rate) in arms.iter_mut().zip(rates) {
+        arm.weight =
+            (1.0 - SCHEDULER_EXPLORATION) * (rate / total) + SCHEDULER_EXPLORATION * uniform;
+        arm.uses /= 2;
+        arm.finds /= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arms(weights: &[f64]) -> Vec<Arm> {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(idx, weight)| Arm::new(idx.to_string(), *weight))
+            .collect()
+    }
+
+    #[test]
+    fn stack_sizes_halve_down_to_one() {
+        assert_eq!(stack_sizes(8), [8, 4, 2, 1]);
+        assert_eq!(stack_sizes(5), [5, 2, 1]);
+        assert_eq!(stack_sizes(0), [1]);
+    }
+
+    #[test]
+    fn pick_follows_the_weights() {
+        let arms = arms(&[2.0, 1.0, 1.0]);
+        assert_eq!(pick(&arms, 0.0), 0);
+        assert_eq!(pick(&arms, 0.49), 0);
+        assert_eq!(pick(&arms, 0.5), 1);
+        assert_eq!(pick(&arms, 0.8), 2);
+        // rounding never picks past the last arm
+        assert_eq!(pick(&arms, 1.0), 2);
+    }
+
+    #[test]
+    fn reweight_favours_finds_and_keeps_exploring() {
+        let mut arms = arms(&[0.5, 0.5]);
+        arms[0].uses = 1000;
+        arms[0].finds = 100;
+        arms[1].uses = 1000;
+        let uniform = 1.0 / arms.len() as f64;
+        reweight(&mut arms);
+        assert!(arms[0].weight > arms[1].weight);
+        assert!(arms[1].weight >= SCHEDULER_EXPLORATION * uniform);
+        assert!((arms.iter().map(|arm| arm.weight).sum::<f64>() - 1.0).abs() < 1e-9);
+        // the counts decay
+        assert_eq!((arms[0].uses, arms[0].finds), (500, 50));
+    }
+
+    #[test]
+    fn init_keeps_learned_weights_of_the_same_mutators() {
+        let mut scheduler = MutatorScheduler::default();
+        scheduler.init(&["a", "b"], 4);
+        assert_eq!(scheduler.stacks.len(), 3);
+        for _ in 0..SCHEDULER_PERIOD / 2 {
+            scheduler.record_mutator(0, true);
+            scheduler.record_mutator(1, false);
+        }
+        let learned = scheduler.mutators[0].weight;
+        assert!(learned > 0.5);
+        scheduler.init(&["a", "b"], 4);
+        assert_eq!(scheduler.mutators[0].weight, learned);
+        scheduler.init(&["a", "c"], 4);
+        assert_eq!(scheduler.mutators[0].weight, 0.5);
+    }
+}
@@ -1,6 +1,7 @@
+use super::scheduler::MutatorScheduler;
 use crate::{
     fuzzer::context::{Context, MutationMetadata},
-    Node, Visitor,
+    CmpTransform, Node, Visitor,
 };
 use libafl::{
     corpus::Corpus,
@@ -78,9 +79,26 @@ impl<I, S> Restartable<S> for StatsStage<I> {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, libafl_bolts::SerdeAny, Default)]
 pub struct AutarkieStats {
     mutations: BTreeMap<MutationMetadata, usize>,
+    /// Learned weights of `AutarkieMutationalStage`
+    pub scheduler: MutatorScheduler,
+    /// Inputs generated per round by `GenerateStage`
+    pub generate_ratio: f64,
+    /// How often each cmplog transformation was tried and found a new corpus entry
+    cmp_transforms: BTreeMap<CmpTransform, TransformStats>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+pub struct TransformStats {
+    tries: usize,
+    finds: usize,
 }
 
 impl AutarkieStats {
+    pub fn add_cmp_transform(&mut self, transform: CmpTransform, found: bool) {
+        let stats = self.cmp_transforms.entry(transform).or_default();
+        stats.tries += 1;
+        stats.finds += found as usize;
+    }
     pub fn add_new_input_mutations(&mut self, mutations: HashSet<MutationMetadata>) {
         for m in mutations {
             self.mutations
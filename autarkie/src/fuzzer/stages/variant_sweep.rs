@@ -0,0 +1,113 @@
+use crate::{fuzzer::context::MutationMetadata, MutationType, Node, Visitor};
+use libafl::{
+    events::EventFirer,
+    executors::Executor,
+    stages::{Restartable, Stage},
+    state::HasCurrentTestcase,
+    Evaluator, HasMetadata,
+};
+use serde::Serialize;
+use std::{cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use crate::fuzzer::context::Context;
+
+/// Deterministically try every other variant of each enum (and Some/None of each Option) of a new
+/// testcase, like AFL's deterministic stage. Fields which fit the new variant are kept.
+/// Runs at most `budget` executions per testcase.
+#[derive(Debug)]
+pub struct VariantSweepStage<I> {
+    visitor: Rc<RefCell<Visitor>>,
+    budget: usize,
+    phantom: PhantomData<I>,
+}
+
+impl<I> VariantSweepStage<I> {
+    pub fn new(visitor: Rc<RefCell<Visitor>>, budget: usize) -> Self {
+        Self {
+            visitor,
+            budget,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E, EM, Z, S, I> Stage<E, EM, S, Z> for VariantSweepStage<I>
+where
+    I: Node + Serialize + Clone,
+    S: HasCurrentTestcase<I> + HasMetadata,
+    E: Executor<EM, I, S, Z>,
+    EM: EventFirer<I, S>,
+    Z: Evaluator<E, EM, I, S>,
+{
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+    ) -> Result<(), libafl::Error> {
+        if self.budget == 0 {
+            return Ok(());
+        }
+        let current = state.current_input_cloned()?;
+        let current_bytes = crate::serialize(&current);
+        let enums = {
+            let mut visitor = self.visitor.borrow_mut();
+            current.__autarkie_fields(&mut visitor, 0);
+            let enums = visitor.enums();
+            // reset the fields
+            visitor.fields();
+            enums
+        };
+        let mut executions = 0;
+        for (path, id, current_variant) in enums {
+            let variant_count = self.visitor.borrow().variant_count(&id);
+            for variant in 0..variant_count {
+                if variant == current_variant
+                    || !self.visitor.borrow().is_variant_enabled(&id, variant)
+                {
+                    continue;
+                }
+                if executions >= self.budget {
+                    return Ok(());
+                }
+                let mut switched = current.clone();
+                {
+                    let mut visitor = self.visitor.borrow_mut();
+                    // drop the chunks of the previous attempt
+                    let _ = visitor.serialized();
+                    let bias = visitor.generate_depth();
+                    #[cfg(feature = "debug_mutators")]
+                    println!("variant_sweep | {:?} | {}", path, variant);
+                    switched.__autarkie_mutate(
+                        &mut MutationType::SwitchVariant(Some(variant), bias),
+                        &mut visitor,
+                        VecDeque::from_iter(path.iter().copied()),
+                    );
+                    // the switch fails for variants we can not generate, eg: serde skipped ones
+                    if visitor.violates_constraints(&switched)
+                        || crate::serialize(&switched) == current_bytes
+                    {
+                        continue;
+                    }
+                }
+                state
+                    .metadata_mut::<Context>()?
+                    .add_mutation(MutationMetadata::VariantSweep);
+                fuzzer.evaluate_input(state, executor, manager, &switched)?;
+                executions += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<I, S> Restartable<S> for VariantSweepStage<I> {
+    fn should_restart(&mut self, state: &mut S) -> Result<bool, libafl::Error> {
+        Ok(true)
+    }
+
+    fn clear_progress(&mut self, state: &mut S) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
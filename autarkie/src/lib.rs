@@ -13,12 +13,15 @@ pub use libafl_bolts::ownedref::OwnedSlice;
 #[cfg(feature = "llvm-fuzzer-no-link")]
 pub use libafl_targets::{libfuzzer_initialize, libfuzzer_test_one_input};
 
+pub mod render;
 pub mod tree;
 pub mod visitor;
+pub use render::*;
 pub use tree::*;
 pub use visitor::*;
 
 mod graph;
+mod regex_sampler;
 
 #[cfg(feature = "scale")]
 pub mod scale;
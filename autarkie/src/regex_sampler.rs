@@ -0,0 +1,115 @@
+//! Generate strings from a regex, used by #[autarkie_regex(..)] fields.
+use libafl_bolts::rands::{Rand, StdRand};
+use regex_syntax::hir::{Class, Hir, HirKind};
+
+/// A parsed regex which can generate matching strings and validate existing ones.
+#[derive(Debug, Clone)]
+pub struct RegexSampler {
+    hir: Hir,
+    /// The anchored regex, so we only accept full matches.
+    regex: regex::Regex,
+}
+
+impl RegexSampler {
+    pub fn new(pattern: &str) -> Self {
+        let hir = regex_syntax::parse(pattern)
+            .unwrap_or_else(|e| panic!("invalid #[autarkie_regex({pattern:?})]: {e}"));
+        let regex = regex::Regex::new(&format!("^(?:{pattern})$"))
+            .unwrap_or_else(|e| panic!("invalid #[autarkie_regex({pattern:?})]: {e}"));
+        Self { hir, regex }
+    }
+
+    /// Generate a string matching the regex.
+    /// Unbounded repetitions are repeated at most `max_repeat` times more than their minimum.
+    /// Look-arounds (eg: \b or ^ inside an alternation) are ignored while sampling, so we retry
+    /// samples which do not match; if none of them do, the last sample is returned anyway.
+    pub fn generate(&self, rng: &mut StdRand, max_repeat: usize) -> String {
+        let mut generated = String::new();
+        for _ in 0..REGEX_RETRIES {
+            let mut out = vec![];
+            sample(&self.hir, rng, max_repeat, &mut out);
+            generated = String::from_utf8_lossy(&out).into_owned();
+            if self.is_match(&generated) {
+                break;
+            }
+        }
+        generated
+    }
+
+    pub fn is_match(&self, input: &str) -> bool {
+        self.regex.is_match(input)
+    }
+}
+
+/// How often we sample a regex before giving up on finding a match
+const REGEX_RETRIES: usize = 16;
+
+fn sample(hir: &Hir, rng: &mut StdRand, max_repeat: usize, out: &mut Vec<u8>) {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => out.extend_from_slice(&literal.0),
+        HirKind::Class(Class::Unicode(class)) => {
+            let total = class
+                .ranges()
+                .iter()
+                .map(|r| (r.end() as usize) - (r.start() as usize) + 1)
+                .sum::<usize>();
+            if total == 0 {
+                return;
+            }
+            let mut pick = rng.between(0, total - 1);
+            for range in class.ranges() {
+                let len = (range.end() as usize) - (range.start() as usize) + 1;
+                if pick < len {
+                    // ranges may span surrogates which are not valid chars
+                    let c =
+                        char::from_u32(range.start() as u32 + pick as u32).unwrap_or(range.start());
+                    let mut buf = [0; 4];
+                    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                    return;
+                }
+                pick -= len;
+            }
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let total = class
+                .ranges()
+                .iter()
+                .map(|r| (r.end() as usize) - (r.start() as usize) + 1)
+                .sum::<usize>();
+            if total == 0 {
+                return;
+            }
+            let mut pick = rng.between(0, total - 1);
+            for range in class.ranges() {
+                let len = (range.end() as usize) - (range.start() as usize) + 1;
+                if pick < len {
+                    out.push(range.start() + pick as u8);
+                    return;
+                }
+                pick -= len;
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            let min = repetition.min as usize;
+            let max = repetition
+                .max
+                .map(|max| max as usize)
+                .unwrap_or(min + max_repeat);
+            for _ in 0..rng.between(min, max) {
+                sample(&repetition.sub, rng, max_repeat, out);
+            }
+        }
+        HirKind::Capture(capture) => sample(&capture.sub, rng, max_repeat, out),
+        HirKind::Concat(hirs) => {
+            for hir in hirs {
+                sample(hir, rng, max_repeat, out);
+            }
+        }
+        HirKind::Alternation(hirs) => {
+            if let Some(hir) = rng.choose(hirs) {
+                sample(hir, rng, max_repeat, out);
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Rendering with provenance: which bytes of the rendered input each node produced.
+//! Lets cmplog match comparisons on the rendered input (eg: "1234" in a SQL query) to the nodes.
+use crate::{CmpOperands, CmpTransform, FieldLocation, Node, Visitor};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Converters which can tell which bytes of the rendered input each node produced.
+/// See the `render = ..` form of `impl_converter!`
+///
+/// NOTE: `run_fuzzer` requires it of every converter. `impl_converter!` implements it for you,
+/// but converters written by hand need an empty `impl ToProvenance<MyInput> for MyConverter {}`,
+/// which keeps the default of not tracking provenance. A blanket impl for every `ToTargetBytes`
+/// would conflict with the converters which do track it.
+pub trait ToProvenance<I> {
+    /// The rendered input and its provenance. None if the converter does not track provenance
+    fn to_provenance(&mut self, input: &I) -> Option<(Vec<u8>, Provenance)> {
+        None
+    }
+}
+
+/// Collects the rendered bytes and the span each node rendered.
+/// Nodes are addressed like the paths of `Node::__autarkie_mutate`: the field index for structs
+/// and enum variants, the element index for iterables and 0 for `Some`. `Box` and the like add nothing.
+#[derive(Debug, Default)]
+pub struct Renderer {
+    output: Vec<u8>,
+    path: Vec<usize>,
+    provenance: Provenance,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append rendered bytes for the current node
+    pub fn write(&mut self, bytes: impl AsRef<[u8]>) {
+        self.output.extend_from_slice(bytes.as_ref());
+    }
+
+    /// Render the child at `index` of the current node, recording the span it wrote
+    pub fn child(&mut self, index: usize, render: impl FnOnce(&mut Self)) {
+        self.path.push(index);
+        let start = self.output.len();
+        render(self);
+        self.provenance
+            .spans
+            .push((self.path.clone(), start..self.output.len()));
+        self.path.pop();
+    }
+
+    pub fn finish(self) -> (Vec<u8>, Provenance) {
+        (self.output, self.provenance)
+    }
+}
+
+impl std::fmt::Write for Renderer {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.write(s);
+        Ok(())
+    }
+}
+
+/// The span of the rendered input each node produced
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    spans: Vec<(Vec<usize>, Range<usize>)>,
+}
+
+impl Provenance {
+    pub fn spans(&self) -> &[(Vec<usize>, Range<usize>)] {
+        &self.spans
+    }
+
+    pub fn span(&self, path: &[usize]) -> Option<Range<usize>> {
+        self.spans
+            .iter()
+            .find(|(node, _)| node == path)
+            .map(|(_, span)| span.clone())
+    }
+
+    /// The deepest node which rendered all of `range`
+    pub fn node_at(&self, range: &Range<usize>) -> Option<&[usize]> {
+        self.spans
+            .iter()
+            .filter(|(_, span)| span.start <= range.start && range.end <= span.end)
+            .max_by_key(|(node, _)| node.len())
+            .map(|(node, _)| node.as_slice())
+    }
+}
+
+/// Match a comparison the target made on the rendered input to the nodes which rendered the operand,
+/// like `Node::__autarkie_cmps` does for the serialized input.
+/// Integer operands are looked for as decimal text, and text which parses as an integer also matches integer nodes.
+pub fn rendered_cmps<I: Node>(
+    input: &I,
+    visitor: &mut Visitor,
+    rendered: &[u8],
+    provenance: &Provenance,
+    operands: CmpOperands,
+) -> Vec<(Vec<FieldLocation>, Vec<u8>, CmpTransform)> {
+    let texts = match operands {
+        CmpOperands::Int(left, right) => {
+            let mut texts = vec![(left.to_string(), right.to_string())];
+            if (left as i64) < 0 || (right as i64) < 0 {
+                texts.push(((left as i64).to_string(), (right as i64).to_string()));
+            }
+            texts
+                .into_iter()
+                .map(|(left, right)| (left.into_bytes(), right.into_bytes()))
+                .collect::<Vec<_>>()
+        }
+        CmpOperands::Bytes(left, right) => vec![(left.to_vec(), right.to_vec())],
+    };
+    let mut matches: Vec<(Vec<FieldLocation>, Vec<u8>, CmpTransform)> = vec![];
+    for (left, right) in &texts {
+        for (from, to) in [(left, right), (right, left)] {
+            if from.is_empty() || from == to || from.len() > rendered.len() {
+                continue;
+            }
+            // the nodes which rendered an occurrence of the operand
+            let mut nodes: Vec<&[usize]> = vec![];
+            for start in 0..=rendered.len() - from.len() {
+                if rendered[start..start + from.len()] != from[..] {
+                    continue;
+                }
+                if let Some(node) = provenance.node_at(&(start..start + from.len())) {
+                    if !nodes.contains(&node) {
+                        nodes.push(node);
+                    }
+                }
+            }
+            if nodes.is_empty() {
+                continue;
+            }
+            let mut candidates = vec![CmpOperands::Bytes(from, to)];
+            if let (Some(from), Some(to)) = (parse_int(from), parse_int(to)) {
+                candidates.push(CmpOperands::Int(from, to));
+            }
+            // the matches do not depend on the occurrence, so we only look for them once
+            for candidate in candidates {
+                input.__autarkie_cmps(visitor, 0, candidate);
+                for matched in visitor.transformed_cmps() {
+                    let path = matched.0.iter().map(|(i, _)| i.0).collect::<Vec<_>>();
+                    let is_known = matches.iter().any(|(known, data, _)| {
+                        *data == matched.1 && known.iter().map(|(i, _)| i.0).eq(path.clone())
+                    });
+                    if nodes.iter().any(|node| path.starts_with(node)) && !is_known {
+                        matches.push(matched);
+                    }
+                }
+            }
+        }
+    }
+    matches
+}
+
+fn parse_int(text: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(text).ok()?;
+    text.parse::<u64>()
+        .ok()
+        .or_else(|| text.parse::<i64>().ok().map(|value| value as u64))
+}
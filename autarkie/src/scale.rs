@@ -38,10 +38,13 @@ macro_rules! impl_generate_compact {
                 &self,
                 v: &mut crate::Visitor,
                 index: usize,
-                __autarkie_val: (u64, u64),
+                __autarkie_val: crate::CmpOperands,
             ) {
-                if __autarkie_val.0 == self.0 as u64 {
-                    v.register_cmp(crate::serialize(&(__autarkie_val.1 as $inner)));
+                let crate::CmpOperands::Int(left, right) = __autarkie_val else {
+                    return;
+                };
+                if left == self.0 as u64 {
+                    v.register_cmp(crate::serialize(&(right as $inner)));
                 };
             }
             fn __autarkie_mutate(
@@ -52,6 +55,9 @@ macro_rules! impl_generate_compact {
             ) {
                 debug_assert!(path.len() == 0);
                 match ty {
+                    MutationType::Copy(buffer) => {
+                        **buffer = crate::serialize(self);
+                    }
                     MutationType::Splice(other) => {
                         // TODO: debug
                         if let Some(compacted) = crate::maybe_deserialize::<Compact<$inner>>(other)
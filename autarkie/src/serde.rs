@@ -1,6 +1,6 @@
 use std::{collections::VecDeque, fmt::Debug};
 
-use crate::{deserialize, serialize, MutationType, Node, Visitor};
+use crate::{deserialize, serialize, CmpOperands, MutationType, Node, Visitor};
 
 macro_rules! impl_node_serde_array {
     ($n: literal) => {
@@ -60,6 +60,9 @@ macro_rules! impl_node_serde_array {
                         .__autarkie_mutate(ty, visitor, path);
                 } else {
                     match ty {
+                        MutationType::Copy(buffer) => {
+                            **buffer = serialize(self);
+                        }
                         MutationType::Splice(other) => {
                             *self = deserialize(other);
                         }
@@ -71,6 +74,9 @@ macro_rules! impl_node_serde_array {
                                 self.__autarkie_serialized(visitor);
                             }
                         }
+                        MutationType::Reorder(reorder) if reorder.preserves_len() => {
+                            crate::reorder_slice(self, reorder, visitor);
+                        }
                         _ => unreachable!("tAL6LPUb____"),
                     }
                 }
@@ -90,8 +96,19 @@ macro_rules! impl_node_serde_array {
                 &self,
                 visitor: &mut Visitor,
                 index: usize,
-                __autarkie_val: (u64, u64),
+                __autarkie_val: CmpOperands,
             ) {
+                if let CmpOperands::Bytes(left, right) = __autarkie_val {
+                    if let Some(this) = (self as &dyn std::any::Any).downcast_ref::<[u8; $n]>() {
+                        // only the replacements which keep the length fit the array
+                        for replaced in crate::byte_cmps(this, left, right) {
+                            if let Ok(replaced) = <[u8; $n]>::try_from(replaced.as_slice()) {
+                                visitor.register_cmp(serialize(&replaced));
+                            }
+                        }
+                        return;
+                    }
+                }
                 for (index, child) in self.iter().enumerate() {
                     visitor.register_field_stack((
                         ((index, child.__autarkie_node_ty(visitor))),
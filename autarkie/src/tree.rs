@@ -4,8 +4,16 @@ use std::any::TypeId;
 use std::borrow::Cow;
 use std::hash::{Hash, Hasher};
 use std::{
-    collections::{BTreeMap, VecDeque},
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque},
+    ffi::OsString,
     marker::PhantomData,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    num::Wrapping,
+    path::PathBuf,
+    rc::Rc,
+    sync::{Arc, OnceLock},
+    time::Duration,
 };
 
 pub type Id = u64;
@@ -18,12 +26,101 @@ pub enum MutationType<'a> {
     Splice(&'a mut &'a [u8]),
     GenerateAppend(usize),
     SpliceAppend(&'a mut &'a [u8]),
+    /// Change a number in place
+    Arithmetic(Arithmetic),
+    /// Swap the contents of a String or Vec<u8> with the buffer, so they can be mutated as bytes.
+    /// Strings which are not valid UTF-8 are repaired.
+    Bytes(&'a mut Vec<u8>),
+    /// Reorder the elements of an iterable in place
+    Reorder(Reorder),
+    /// Serialize the node into the buffer, leaving it untouched
+    Copy(&'a mut Vec<u8>),
+    /// Switch an enum to the given variant, or a random one, keeping the fields whose type the
+    /// new variant also has. The other fields are generated with the bias.
+    SwitchVariant(Option<usize>, usize),
 }
 
-#[derive(Debug)]
+/// Small in-place changes to numbers, see `MutationType::Arithmetic`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arithmetic {
+    Add(u8),
+    Sub(u8),
+    /// The bit is taken modulo the number's bit width
+    FlipBit(u32),
+    SwapBytes,
+    Negate,
+}
+
+/// The operands of a comparison the target made, see `Node::__autarkie_cmps`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOperands<'a> {
+    /// Matched by integer nodes
+    Int(u64, u64),
+    /// Matched by `String`, `Vec<u8>` and `[u8; N]` nodes, also inside them
+    Bytes(&'a [u8], &'a [u8]),
+}
+
+/// How a node's value was transformed before the target compared it, see `int_cmps`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum CmpTransform {
+    /// Compared as is
+    Identity,
+    /// Compared with the other endianness
+    ByteSwap,
+    /// Compared after widening with the other signedness
+    SignExtend,
+    /// Compared as is, but we write the operand plus or minus one, for `<` and `>`
+    OffByOne,
+    /// Compared after adding a small constant
+    Offset,
+}
+
+/// Max constant we undo for `CmpTransform::Offset`
+pub const CMP_MAX_OFFSET: u64 = 64;
+
+/// In-place reordering of an iterable's elements, see `MutationType::Reorder`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reorder {
+    /// Swap two elements
+    Swap(usize, usize),
+    /// Rotate the subslice left, moving its first elements to its end
+    Rotate(std::ops::Range<usize>, usize),
+    Reverse(std::ops::Range<usize>),
+    Shuffle(std::ops::Range<usize>),
+    /// Insert a copy of the element after it
+    Duplicate(usize),
+}
+
+impl Reorder {
+    /// Whether the reordering keeps the iterable's length, so it can be used on fixed length iterables
+    pub fn preserves_len(&self) -> bool {
+        !matches!(self, Reorder::Duplicate(_))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum GenerateSettings {
     Length(usize),
-    Range(std::ops::RangeInclusive<usize>),
+    /// An inclusive range for integers or the length of iterables, set using #[autarkie_range(..)]
+    Range(std::ops::RangeInclusive<i128>, Distribution),
+    /// An inclusive range for floats, set using #[autarkie_range(..)]
+    FloatRange(std::ops::RangeInclusive<f64>, Distribution),
+    /// A distribution over the whole range of a number, set using #[autarkie_distribution(..)]
+    Distribution(Distribution),
+}
+
+/// How numbers are picked, set using #[autarkie_distribution(..)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    Uniform,
+    /// The bounds, zero and their neighbours
+    Boundary,
+    /// Values close to zero
+    Small,
+    /// Values whose distance from zero is uniformly distributed on a log scale
+    LogUniform,
 }
 
 macro_rules! node {
@@ -62,7 +159,7 @@ where
 
     fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {}
 
-    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {}
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {}
 
     fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
         NodeType::NonRecursive
@@ -70,9 +167,27 @@ where
 
     fn __autarkie_serialized(&self, visitor: &mut Visitor) {}
 
+    /// Whether Self satisfies a field's settings (eg: #[autarkie_range(..)])
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        true
+    }
+
+    /// Repair Self so it satisfies a field's settings again after a mutation
+    fn __autarkie_constrain(&mut self, visitor: &mut Visitor, settings: &GenerateSettings) {
+        if !self.__autarkie_satisfies(settings) {
+            let settings = Some(settings.clone());
+            if let Some(generated) = Self::__autarkie_generate(visitor, &mut 0, 0, settings) {
+                *self = generated;
+            }
+        }
+    }
+
     fn __autarkie_mutate(&mut self, ty: &mut MutationType, visitor: &mut Visitor, path: VecDeque<usize>) {
         debug_assert!(path.len() == 0);
         match ty {
+            MutationType::Copy(buffer) => {
+                **buffer = serialize(self);
+            }
             MutationType::Splice(other) => {
                 *self = deserialize(other);
             }
@@ -110,6 +225,8 @@ impl<T: 'static> Node for PhantomData<T> {
     }
 }
 
+/// Transparent, like Box<T>.
+/// Mutations clone a borrowed value once, after which it is mutated in place.
 impl<T: 'static + Node + Clone> Node for Cow<'static, T> {
     fn __autarkie_generate(
         visitor: &mut Visitor,
@@ -118,13 +235,64 @@ impl<T: 'static + Node + Clone> Node for Cow<'static, T> {
         settings: Option<GenerateSettings>,
     ) -> Option<Self> {
         Some(Cow::Owned(T::__autarkie_generate(
-            visitor, depth, cur_depth, None,
+            visitor, depth, cur_depth, settings,
         )?))
     }
+
+    fn inner_id() -> Id {
+        T::__autarkie_id()
+    }
+
+    fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+        if !v.is_recursive(T::__autarkie_id()) {
+            T::__autarkie_register(v, parent, variant);
+        } else {
+            v.register_ty(parent, T::__autarkie_id_tuple(), variant);
+            v.pop_ty();
+        }
+    }
+
+    fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
+        self.as_ref().__autarkie_node_ty(visitor)
+    }
+
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        self.as_ref().__autarkie_satisfies(settings)
+    }
+
+    fn __autarkie_constrain(&mut self, visitor: &mut Visitor, settings: &GenerateSettings) {
+        if !self.__autarkie_satisfies(settings) {
+            self.to_mut().__autarkie_constrain(visitor, settings);
+        }
+    }
+
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        self.as_ref()
+            .__autarkie_cmps(visitor, index, __autarkie_val);
+    }
+
+    fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+        self.as_ref().__autarkie_fields(visitor, index);
+    }
+
+    fn __autarkie_mutate(
+        &mut self,
+        ty: &mut MutationType,
+        visitor: &mut Visitor,
+        path: VecDeque<usize>,
+    ) {
+        match ty {
+            // no need to clone borrowed data to read it
+            MutationType::Copy(buffer) if path.is_empty() => {
+                **buffer = serialize(self);
+            }
+            _ => self.to_mut().__autarkie_mutate(ty, visitor, path),
+        }
+    }
+
     fn __autarkie_serialized(&self, visitor: &mut Visitor) {
         self.as_ref().__autarkie_serialized(visitor);
     }
-    // TODO: fields / mutate
 }
 
 impl Node for () {
@@ -149,13 +317,7 @@ where
         cur_depth: usize,
         settings: Option<GenerateSettings>,
     ) -> Option<Self> {
-        let element_count = if let Some(GenerateSettings::Length(len)) = settings {
-            len
-        } else if let Some(GenerateSettings::Range(range)) = settings {
-            visitor.random_range(*range.start(), *range.end() + 1)
-        } else {
-            visitor.random_range(0, visitor.iterate_depth())
-        };
+        let element_count = iterable_len(visitor, &settings);
         if element_count == 0 {
             return Some(vec![].into());
         }
@@ -183,6 +345,17 @@ where
         T::__autarkie_id()
     }
 
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        length_settings_bounds(settings)
+            .map_or(true, |(min, max)| (min..=max).contains(&self.len()))
+    }
+
+    fn __autarkie_constrain(&mut self, visitor: &mut Visitor, settings: &GenerateSettings) {
+        if !self.__autarkie_satisfies(settings) {
+            self.to_mut().__autarkie_constrain(visitor, settings);
+        }
+    }
+
     fn __autarkie_serialized(&self, visitor: &mut Visitor) {
         for item in self.as_ref() {
             visitor.add_serialized(serialize(&item), T::__autarkie_id());
@@ -190,54 +363,19 @@ where
         }
     }
 
+    /// Mutated as a Vec<T>, which we only clone once if the slice is borrowed.
     fn __autarkie_mutate(
         &mut self,
         ty: &mut MutationType,
         visitor: &mut Visitor,
-        mut path: VecDeque<usize>,
+        path: VecDeque<usize>,
     ) {
-        if let Some(popped) = path.pop_front() {
-            let mut cloned = self.as_ref().to_vec();
-            cloned
-                .get_mut(popped)
-                .expect("UbEi1VMg____")
-                .__autarkie_mutate(ty, visitor, path);
-            *self = cloned.into();
-        } else {
-            match ty {
-                MutationType::Splice(other) => {
-                    *self = deserialize(other);
-                }
-                MutationType::GenerateReplace(ref mut bias) => {
-                    if let Some(generated) = Self::__autarkie_generate(visitor, bias, 0, None) {
-                        *self = generated;
-                        self.__autarkie_serialized(visitor);
-                    }
-                }
-                MutationType::SpliceAppend(other) => {
-                    // TODO: make more performant
-                    let mut cloned = self.as_ref().to_vec();
-                    cloned.push(deserialize(other));
-                    *self = cloned.into();
-                }
-                MutationType::GenerateAppend(bias) => {
-                    if let Some(generated) = T::__autarkie_generate(visitor, bias, 0, None) {
-                        // TODO: make more performant
-                        let mut cloned = self.as_ref().to_vec();
-                        cloned.push(generated);
-                        *self = cloned.into();
-                    }
-                }
-                MutationType::IterablePop(ref mut bias) => {
-                    // TODO: make more performant
-                    let mut cloned = self.as_ref().to_vec();
-                    cloned.remove(*bias);
-                    *self = cloned.into();
-                }
-                MutationType::RecursiveReplace => {
-                    // TODO
-                }
+        match ty {
+            // no need to clone borrowed data to read it
+            MutationType::Copy(buffer) if path.is_empty() => {
+                **buffer = serialize(self);
             }
+            _ => self.to_mut().__autarkie_mutate(ty, visitor, path),
         }
     }
 
@@ -252,7 +390,7 @@ where
         }
     }
 
-    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
         for (index, child) in self.iter().enumerate() {
             visitor.register_field_stack((
                 ((index, child.__autarkie_node_ty(visitor))),
@@ -317,6 +455,9 @@ where
                 .__autarkie_mutate(ty, visitor, path);
         } else {
             match ty {
+                MutationType::Copy(buffer) => {
+                    **buffer = serialize(self);
+                }
                 MutationType::Splice(other) => {
                     *self = deserialize(other);
                 }
@@ -326,6 +467,9 @@ where
                         self.__autarkie_serialized(visitor);
                     }
                 }
+                MutationType::Reorder(reorder) if reorder.preserves_len() => {
+                    reorder_slice(self, reorder, visitor);
+                }
                 _ => unreachable!("tAL6LPUb____"),
             }
         }
@@ -341,7 +485,7 @@ where
         }
     }
 
-    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
         for (index, child) in self.iter().enumerate() {
             visitor.register_field_stack((
                 ((index, child.__autarkie_node_ty(visitor))),
@@ -363,13 +507,7 @@ where
         cur_depth: usize,
         settings: Option<GenerateSettings>,
     ) -> Option<Self> {
-        let element_count = if let Some(GenerateSettings::Length(len)) = settings {
-            len
-        } else if let Some(GenerateSettings::Range(range)) = settings {
-            visitor.random_range(*range.start(), *range.end() + 1)
-        } else {
-            visitor.random_range(0, visitor.iterate_depth())
-        };
+        let element_count = iterable_len(visitor, &settings);
         if element_count == 0 {
             return Some(vec![]);
         }
@@ -397,6 +535,24 @@ where
         T::__autarkie_id()
     }
 
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        length_settings_bounds(settings)
+            .map_or(true, |(min, max)| (min..=max).contains(&self.len()))
+    }
+
+    fn __autarkie_constrain(&mut self, visitor: &mut Visitor, settings: &GenerateSettings) {
+        let Some((min, max)) = length_settings_bounds(settings) else {
+            return;
+        };
+        self.truncate(max);
+        while self.len() < min {
+            let Some(generated) = T::__autarkie_generate(visitor, &mut 0, 0, None) else {
+                break;
+            };
+            self.push(generated);
+        }
+    }
+
     fn __autarkie_serialized(&self, visitor: &mut Visitor) {
         for item in self {
             visitor.add_serialized(serialize(&item), T::__autarkie_id());
@@ -416,6 +572,9 @@ where
                 .__autarkie_mutate(ty, visitor, path);
         } else {
             match ty {
+                MutationType::Copy(buffer) => {
+                    **buffer = serialize(self);
+                }
                 MutationType::Splice(other) => {
                     *self = deserialize(other);
                 }
@@ -436,8 +595,33 @@ where
                 MutationType::IterablePop(ref mut bias) => {
                     self.remove(*bias);
                 }
+                MutationType::Arithmetic(_) => {
+                    unreachable!("____Y1OKSP9P")
+                }
+                MutationType::SwitchVariant(..) => {
+                    unreachable!("____Lq3ZcN8u")
+                }
+                MutationType::Bytes(bytes) => {
+                    let Some(this) = (self as &mut dyn std::any::Any).downcast_mut::<Vec<u8>>()
+                    else {
+                        unreachable!("____SLkvWNI6");
+                    };
+                    std::mem::swap(this, *bytes);
+                }
+                MutationType::Reorder(Reorder::Duplicate(index)) => {
+                    let item = serialize(&self[*index]);
+                    self.insert(*index + 1, deserialize(&mut item.as_slice()));
+                }
+                MutationType::Reorder(reorder) => {
+                    reorder_slice(self, reorder, visitor);
+                }
                 MutationType::RecursiveReplace => {
-                    // TODO
+                    // replace the recursive items with non-recursive ones
+                    for item in self.iter_mut() {
+                        if item.__autarkie_node_ty(visitor).is_recursive() {
+                            item.__autarkie_mutate(ty, visitor, VecDeque::new());
+                        }
+                    }
                 }
             }
         }
@@ -454,7 +638,15 @@ where
         }
     }
 
-    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        if let CmpOperands::Bytes(left, right) = __autarkie_val {
+            if let Some(this) = (self as &dyn std::any::Any).downcast_ref::<Vec<u8>>() {
+                for replaced in byte_cmps(this, left, right) {
+                    visitor.register_cmp(serialize(&replaced));
+                }
+                return;
+            }
+        }
         for (index, child) in self.iter().enumerate() {
             visitor.register_field_stack((
                 ((index, child.__autarkie_node_ty(visitor))),
@@ -509,7 +701,7 @@ where
         self.as_ref().__autarkie_node_ty(visitor)
     }
 
-    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
         self.as_ref()
             .__autarkie_cmps(visitor, index, __autarkie_val);
     }
@@ -594,6 +786,9 @@ where
             self.as_mut().unwrap().__autarkie_mutate(ty, visitor, path);
         } else {
             match ty {
+                MutationType::Copy(buffer) => {
+                    **buffer = serialize(self);
+                }
                 MutationType::Splice(other) => {
                     *self = deserialize(other);
                 }
@@ -604,6 +799,17 @@ where
                         self.__autarkie_serialized(visitor);
                     }
                 }
+                MutationType::SwitchVariant(variant, ref mut bias) => {
+                    // Some is variant 0 and None is variant 1
+                    if !variant.map_or(self.is_none(), |variant| variant == 0) {
+                        *self = None;
+                    } else if self.is_none() {
+                        if let Some(generated) = T::__autarkie_generate(visitor, bias, 0, None) {
+                            *self = Some(generated);
+                            self.__autarkie_serialized(visitor);
+                        }
+                    }
+                }
                 _ => {
                     unreachable!()
                 }
@@ -622,6 +828,7 @@ where
     }
 
     fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+        visitor.visit_variant(Self::__autarkie_id(), if self.is_some() { 0 } else { 1 });
         if let Some(inner) = self {
             visitor.register_field_stack((
                 (index, inner.__autarkie_node_ty(visitor)),
@@ -632,7 +839,7 @@ where
         }
     }
 
-    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
         if let Some(inner) = self {
             visitor.register_field_stack((
                 (index, inner.__autarkie_node_ty(visitor)),
@@ -711,6 +918,9 @@ where
             }
         } else {
             match ty {
+                MutationType::Copy(buffer) => {
+                    **buffer = serialize(self);
+                }
                 MutationType::Splice(other) => {
                     *self = deserialize(other);
                 }
@@ -753,7 +963,7 @@ where
         visitor.pop_field();
     }
 
-    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
         visitor.register_field_stack((
             (index, self.__autarkie_node_ty(visitor)),
             Self::__autarkie_id(),
@@ -776,6 +986,52 @@ impl Node for std::string::String {
     ) -> Option<Self> {
         Some(visitor.get_string())
     }
+
+    fn __autarkie_mutate(
+        &mut self,
+        ty: &mut MutationType,
+        visitor: &mut Visitor,
+        path: VecDeque<usize>,
+    ) {
+        debug_assert!(path.is_empty());
+        match ty {
+            MutationType::Bytes(bytes) => {
+                let mut this = std::mem::take(self).into_bytes();
+                std::mem::swap(&mut this, *bytes);
+                *self = match String::from_utf8(this) {
+                    Ok(string) => string,
+                    Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+                };
+            }
+            MutationType::Copy(buffer) => {
+                **buffer = serialize(self);
+            }
+            MutationType::Splice(other) => {
+                *self = deserialize(other);
+            }
+            MutationType::GenerateReplace(ref mut bias) => {
+                if let Some(generated) = Self::__autarkie_generate(visitor, bias, 0, None) {
+                    *self = generated;
+                    visitor.add_serialized(serialize(self), Self::__autarkie_id());
+                }
+            }
+            _ => {
+                unreachable!()
+            }
+        }
+    }
+
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        let CmpOperands::Bytes(left, right) = __autarkie_val else {
+            return;
+        };
+        for replaced in byte_cmps(self.as_bytes(), left, right) {
+            // operands which break the encoding can not be spliced into a String
+            if let Ok(replaced) = String::from_utf8(replaced) {
+                visitor.register_cmp(serialize(&replaced));
+            }
+        }
+    }
 }
 
 impl Node for Box<str> {
@@ -807,10 +1063,149 @@ impl Node for char {
     }
 }
 
-impl<K, V> Node for BTreeMap<K, V>
+/// Shared pointers are transparent, like Box<T>.
+/// Mutations clone the inner value if it is shared.
+macro_rules! impl_node_shared {
+    ($ptr: ident) => {
+        impl<T> Node for $ptr<T>
+        where
+            T: Node + Clone,
+        {
+            fn __autarkie_generate(
+                visitor: &mut Visitor,
+                depth: &mut usize,
+                cur_depth: usize,
+                settings: Option<GenerateSettings>,
+            ) -> Option<Self> {
+                Some($ptr::new(T::__autarkie_generate(
+                    visitor, depth, cur_depth, settings,
+                )?))
+            }
+
+            fn inner_id() -> Id {
+                T::__autarkie_id()
+            }
+
+            fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+                if !v.is_recursive(T::__autarkie_id()) {
+                    T::__autarkie_register(v, parent, variant);
+                } else {
+                    v.register_ty(parent, T::__autarkie_id_tuple(), variant);
+                    v.pop_ty();
+                }
+            }
+
+            fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
+                self.as_ref().__autarkie_node_ty(visitor)
+            }
+
+            fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+                self.as_ref().__autarkie_satisfies(settings)
+            }
+
+            fn __autarkie_cmps(
+                &self,
+                visitor: &mut Visitor,
+                index: usize,
+                __autarkie_val: CmpOperands,
+            ) {
+                self.as_ref()
+                    .__autarkie_cmps(visitor, index, __autarkie_val);
+            }
+
+            fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+                self.as_ref().__autarkie_fields(visitor, index);
+            }
+
+            fn __autarkie_mutate(
+                &mut self,
+                ty: &mut MutationType,
+                visitor: &mut Visitor,
+                path: VecDeque<usize>,
+            ) {
+                match ty {
+                    // no need to clone shared data to read it
+                    MutationType::Copy(buffer) if path.is_empty() => {
+                        **buffer = serialize(self);
+                    }
+                    _ => $ptr::make_mut(self).__autarkie_mutate(ty, visitor, path),
+                }
+            }
+
+            fn __autarkie_serialized(&self, visitor: &mut Visitor) {
+                self.as_ref().__autarkie_serialized(visitor)
+            }
+        }
+    };
+}
+
+impl_node_shared!(Rc);
+impl_node_shared!(Arc);
+
+/// NonZero numbers are generated like their integer, but never zero.
+macro_rules! impl_node_non_zero {
+    ($type: ty, $int: ty) => {
+        impl Node for $type {
+            fn __autarkie_generate(
+                v: &mut Visitor,
+                depth: &mut usize,
+                cur_depth: usize,
+                settings: Option<GenerateSettings>,
+            ) -> Option<Self> {
+                let generated = <$int>::__autarkie_generate(v, depth, cur_depth, settings.clone())?;
+                if let Some(generated) = Self::new(generated) {
+                    return Some(generated);
+                }
+                // we got zero, so the range contains zero and we step to either side of it.
+                [1 as $int, (0 as $int).wrapping_sub(1)]
+                    .into_iter()
+                    .filter_map(Self::new)
+                    .find(|candidate| {
+                        settings
+                            .as_ref()
+                            .map_or(true, |settings| candidate.__autarkie_satisfies(settings))
+                    })
+            }
+            fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+                self.get().__autarkie_satisfies(settings)
+            }
+            fn __autarkie_cmps(&self, v: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+                // a zero operand cannot be spliced into Self
+                if !matches!(
+                    __autarkie_val,
+                    CmpOperands::Int(0, _) | CmpOperands::Int(_, 0)
+                ) {
+                    let from = v.cmps_len();
+                    self.get().__autarkie_cmps(v, index, __autarkie_val);
+                    // nor can a replacement which is zero once transformed back (eg: off by one
+                    // from 1) or truncated (eg: 256 for NonZeroU8)
+                    v.retain_cmps(from, |data| deserialize::<$int>(&mut &data[..]) != 0);
+                }
+            }
+        }
+    };
+}
+
+impl_node_non_zero!(std::num::NonZeroU8, u8);
+impl_node_non_zero!(std::num::NonZeroU16, u16);
+impl_node_non_zero!(std::num::NonZeroU32, u32);
+impl_node_non_zero!(std::num::NonZeroU64, u64);
+impl_node_non_zero!(std::num::NonZeroU128, u128);
+impl_node_non_zero!(std::num::NonZeroI8, i8);
+impl_node_non_zero!(std::num::NonZeroI16, i16);
+impl_node_non_zero!(std::num::NonZeroI32, i32);
+impl_node_non_zero!(std::num::NonZeroI64, i64);
+impl_node_non_zero!(std::num::NonZeroI128, i128);
+#[cfg(not(feature = "scale"))]
+impl_node_non_zero!(std::num::NonZeroUsize, usize);
+#[cfg(not(feature = "scale"))]
+impl_node_non_zero!(std::num::NonZeroIsize, isize);
+
+/// Transparent, so #[autarkie_range(..)] applies to the inner number.
+#[cfg(not(feature = "scale"))]
+impl<T> Node for Wrapping<T>
 where
-    K: Node + Clone + Ord,
-    V: Node + Clone,
+    T: Node,
 {
     fn __autarkie_generate(
         visitor: &mut Visitor,
@@ -818,89 +1213,358 @@ where
         cur_depth: usize,
         settings: Option<GenerateSettings>,
     ) -> Option<Self> {
-        Some(BTreeMap::new())
+        Some(Wrapping(T::__autarkie_generate(
+            visitor, depth, cur_depth, settings,
+        )?))
     }
 
-    fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
-        // TODO: does deferencing clone?
-        for (index, (k, v)) in self.into_iter().enumerate() {
-            visitor
-                .register_field_stack(((index, NodeType::NonRecursive), <(K, V)>::__autarkie_id()));
-            k.__autarkie_fields(visitor, 0);
-            v.__autarkie_fields(visitor, 1);
-            visitor.pop_field();
-        }
+    fn inner_id() -> Id {
+        T::__autarkie_id()
     }
 
-    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
-        for (index, (k, v)) in self.into_iter().enumerate() {
-            visitor
-                .register_field_stack(((index, NodeType::NonRecursive), <(K, V)>::__autarkie_id()));
-            k.__autarkie_cmps(visitor, 0, __autarkie_val);
-            v.__autarkie_cmps(visitor, 1, __autarkie_val);
-            visitor.pop_field();
+    fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+        if !v.is_recursive(T::__autarkie_id()) {
+            T::__autarkie_register(v, parent, variant);
+        } else {
+            v.register_ty(parent, T::__autarkie_id_tuple(), variant);
+            v.pop_ty();
         }
     }
 
     fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
-        NodeType::Iterable(false, self.len(), Self::__autarkie_id())
+        self.0.__autarkie_node_ty(visitor)
+    }
+
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        self.0.__autarkie_satisfies(settings)
+    }
+
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        self.0.__autarkie_cmps(visitor, index, __autarkie_val);
+    }
+
+    fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+        self.0.__autarkie_fields(visitor, index);
     }
 
     fn __autarkie_mutate(
         &mut self,
         ty: &mut MutationType,
         visitor: &mut Visitor,
-        mut path: VecDeque<usize>,
+        path: VecDeque<usize>,
     ) {
-        if let Some(popped) = path.pop_front() {
-            let mut entry_to_modify = None;
-            for (i, (k, v)) in self.iter().enumerate() {
-                if i == popped {
-                    entry_to_modify = Some(k.clone());
-                    break;
-                }
-            }
-            let mut entry_to_modify = entry_to_modify.expect("XaLl1F31____");
-            // we are mutating the (k, v) tuple
-            if path.is_empty() {
-                // let's remove the entry.
-                self.remove(&entry_to_modify).expect("WDZstzcR____");
-                match ty {
-                    MutationType::Splice(other) => {
-                        let (k, v) = deserialize(other);
-                        self.insert(k, v);
-                    }
-                    MutationType::GenerateReplace(bias) => {
-                        let Some(key) = K::__autarkie_generate(visitor, bias, 0, None) else {
-                            return;
-                        };
-                        let Some(__autarkie_val) = V::__autarkie_generate(visitor, bias, 0, None)
-                        else {
-                            return;
-                        };
-                        self.insert(key, __autarkie_val);
-                        self.__autarkie_serialized(visitor);
-                        visitor.add_serialized(serialize(self), Self::__autarkie_id());
-                    }
-                    _ => unreachable!(),
-                }
-            } else {
-                // We are mutating either the key or the value.
-                let inner_popped = path.pop_front().expect("YQ8z8tF8____");
-                // key == 0; value == 1
-                debug_assert!(inner_popped == 0 || inner_popped == 1);
-                if inner_popped == 0 {
-                    let __autarkie_val = self.remove(&entry_to_modify).expect("WDZstzcR____");
-                    entry_to_modify.__autarkie_mutate(ty, visitor, path);
-                    self.insert(entry_to_modify, __autarkie_val);
-                } else {
-                    self.get_mut(&entry_to_modify)
-                        .expect("yMhZ8dor____")
-                        .__autarkie_mutate(ty, visitor, path);
-                }
-            }
-        } else {
+        self.0.__autarkie_mutate(ty, visitor, path);
+    }
+
+    fn __autarkie_serialized(&self, visitor: &mut Visitor) {
+        self.0.__autarkie_serialized(visitor)
+    }
+}
+
+#[cfg(not(feature = "scale"))]
+impl<T> Node for Cell<T>
+where
+    T: Node + Copy,
+{
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        Some(Cell::new(T::__autarkie_generate(
+            visitor, depth, cur_depth, settings,
+        )?))
+    }
+
+    fn inner_id() -> Id {
+        T::__autarkie_id()
+    }
+
+    fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+        if !v.is_recursive(T::__autarkie_id()) {
+            T::__autarkie_register(v, parent, variant);
+        } else {
+            v.register_ty(parent, T::__autarkie_id_tuple(), variant);
+            v.pop_ty();
+        }
+    }
+
+    fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
+        self.get().__autarkie_node_ty(visitor)
+    }
+
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        self.get().__autarkie_satisfies(settings)
+    }
+
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        self.get().__autarkie_cmps(visitor, index, __autarkie_val);
+    }
+
+    fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+        self.get().__autarkie_fields(visitor, index);
+    }
+
+    fn __autarkie_mutate(
+        &mut self,
+        ty: &mut MutationType,
+        visitor: &mut Visitor,
+        path: VecDeque<usize>,
+    ) {
+        self.get_mut().__autarkie_mutate(ty, visitor, path);
+    }
+
+    fn __autarkie_serialized(&self, visitor: &mut Visitor) {
+        self.get().__autarkie_serialized(visitor)
+    }
+}
+
+/// NOTE: the inner value is borrowed, so do not hold a mutable borrow while fuzzing.
+#[cfg(not(feature = "scale"))]
+impl<T> Node for RefCell<T>
+where
+    T: Node,
+{
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        Some(RefCell::new(T::__autarkie_generate(
+            visitor, depth, cur_depth, settings,
+        )?))
+    }
+
+    fn inner_id() -> Id {
+        T::__autarkie_id()
+    }
+
+    fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+        if !v.is_recursive(T::__autarkie_id()) {
+            T::__autarkie_register(v, parent, variant);
+        } else {
+            v.register_ty(parent, T::__autarkie_id_tuple(), variant);
+            v.pop_ty();
+        }
+    }
+
+    fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
+        self.borrow().__autarkie_node_ty(visitor)
+    }
+
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        self.borrow().__autarkie_satisfies(settings)
+    }
+
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        self.borrow()
+            .__autarkie_cmps(visitor, index, __autarkie_val);
+    }
+
+    fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+        self.borrow().__autarkie_fields(visitor, index);
+    }
+
+    fn __autarkie_mutate(
+        &mut self,
+        ty: &mut MutationType,
+        visitor: &mut Visitor,
+        path: VecDeque<usize>,
+    ) {
+        self.get_mut().__autarkie_mutate(ty, visitor, path);
+    }
+
+    fn __autarkie_serialized(&self, visitor: &mut Visitor) {
+        self.borrow().__autarkie_serialized(visitor)
+    }
+}
+
+impl Node for Duration {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        let secs = u64::__autarkie_generate(visitor, depth, cur_depth, None)?;
+        // nanos must stay below a second, otherwise they carry over into secs and may overflow.
+        let nanos = visitor.random_range(0, 999_999_999) as u32;
+        Some(Duration::new(secs, nanos))
+    }
+}
+
+#[cfg(not(feature = "scale"))]
+impl Node for Ipv4Addr {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        u32::__autarkie_generate(visitor, depth, cur_depth, None).map(Ipv4Addr::from)
+    }
+
+    fn __autarkie_cmps(&self, v: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        let CmpOperands::Int(left, right) = __autarkie_val else {
+            return;
+        };
+        let addr = u32::from(*self) as u64;
+        if left == addr {
+            v.register_cmp(serialize(&Ipv4Addr::from(right as u32)));
+        } else if right == addr {
+            v.register_cmp(serialize(&Ipv4Addr::from(left as u32)));
+        }
+    }
+}
+
+#[cfg(not(feature = "scale"))]
+impl Node for Ipv6Addr {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        u128::__autarkie_generate(visitor, depth, cur_depth, None).map(Ipv6Addr::from)
+    }
+}
+
+#[cfg(not(feature = "scale"))]
+impl Node for IpAddr {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        if visitor.coinflip() {
+            Ipv4Addr::__autarkie_generate(visitor, depth, cur_depth, None).map(IpAddr::V4)
+        } else {
+            Ipv6Addr::__autarkie_generate(visitor, depth, cur_depth, None).map(IpAddr::V6)
+        }
+    }
+}
+
+#[cfg(not(feature = "scale"))]
+impl Node for SocketAddrV4 {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        let ip = Ipv4Addr::__autarkie_generate(visitor, depth, cur_depth, None)?;
+        let port = u16::__autarkie_generate(visitor, depth, cur_depth, None)?;
+        Some(SocketAddrV4::new(ip, port))
+    }
+}
+
+/// The flowinfo and scope id are not serialized, so we leave them at zero.
+#[cfg(not(feature = "scale"))]
+impl Node for SocketAddrV6 {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        let ip = Ipv6Addr::__autarkie_generate(visitor, depth, cur_depth, None)?;
+        let port = u16::__autarkie_generate(visitor, depth, cur_depth, None)?;
+        Some(SocketAddrV6::new(ip, port, 0, 0))
+    }
+}
+
+#[cfg(not(feature = "scale"))]
+impl Node for SocketAddr {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        if visitor.coinflip() {
+            SocketAddrV4::__autarkie_generate(visitor, depth, cur_depth, None).map(SocketAddr::V4)
+        } else {
+            SocketAddrV6::__autarkie_generate(visitor, depth, cur_depth, None).map(SocketAddr::V6)
+        }
+    }
+}
+
+#[cfg(not(feature = "scale"))]
+impl Node for PathBuf {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        Some(PathBuf::from(visitor.get_string()))
+    }
+}
+
+#[cfg(not(feature = "scale"))]
+impl Node for OsString {
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        Some(OsString::from(visitor.get_string()))
+    }
+}
+
+/// Like a (T, T) tuple. The start may be greater than the end.
+#[cfg(not(feature = "scale"))]
+impl<T> Node for std::ops::Range<T>
+where
+    T: Node,
+{
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        let start = T::__autarkie_generate(visitor, depth, cur_depth, None)?;
+        let end = T::__autarkie_generate(visitor, depth, cur_depth, None)?;
+        Some(start..end)
+    }
+
+    fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+        v.register_ty(parent, Self::__autarkie_id_tuple(), variant);
+        if !v.is_recursive(T::__autarkie_id()) {
+            T::__autarkie_register(v, Some(Self::__autarkie_id_tuple()), 0);
+        } else {
+            v.register_ty(
+                Some(Self::__autarkie_id_tuple()),
+                T::__autarkie_id_tuple(),
+                0,
+            );
+            v.pop_ty();
+        }
+        v.pop_ty();
+    }
+
+    fn __autarkie_mutate(
+        &mut self,
+        ty: &mut MutationType,
+        visitor: &mut Visitor,
+        mut path: VecDeque<usize>,
+    ) {
+        if let Some(popped) = path.pop_front() {
+            match popped {
+                0 => self.start.__autarkie_mutate(ty, visitor, path),
+                1 => self.end.__autarkie_mutate(ty, visitor, path),
+                _ => unreachable!("____mV8gQe2L"),
+            }
+        } else {
             match ty {
+                MutationType::Copy(buffer) => {
+                    **buffer = serialize(self);
+                }
                 MutationType::Splice(other) => {
                     *self = deserialize(other);
                 }
@@ -911,42 +1575,576 @@ where
                         visitor.add_serialized(serialize(self), Self::__autarkie_id());
                     }
                 }
-                MutationType::SpliceAppend(other) => {
-                    let (k, v) = deserialize(other);
-                    self.insert(k, v);
+                _ => {
+                    unreachable!()
+                }
+            }
+        }
+    }
+
+    fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+        for (index, bound) in [&self.start, &self.end].into_iter().enumerate() {
+            visitor.register_field_stack(((index, NodeType::NonRecursive), T::__autarkie_id()));
+            bound.__autarkie_fields(visitor, 0);
+            visitor.pop_field();
+        }
+    }
+
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        for (index, bound) in [&self.start, &self.end].into_iter().enumerate() {
+            visitor.register_field_stack(((index, NodeType::NonRecursive), T::__autarkie_id()));
+            bound.__autarkie_cmps(visitor, 0, __autarkie_val);
+            visitor.pop_field();
+        }
+    }
+
+    fn __autarkie_serialized(&self, visitor: &mut Visitor) {
+        for bound in [&self.start, &self.end] {
+            visitor.add_serialized(serialize(bound), T::__autarkie_id());
+            bound.__autarkie_serialized(visitor);
+        }
+    }
+}
+
+/// Maps are iterables of (K, V) entries, in iteration order.
+/// Mutations never overwrite another entry, so no entries are lost when a key collides.
+macro_rules! impl_node_map {
+    ($map: ident, $($bound: tt)+) => {
+        impl<K, V> Node for $map<K, V>
+        where
+            K: Node + Clone + $($bound)+,
+            V: Node + Clone,
+        {
+            fn __autarkie_generate(
+                visitor: &mut Visitor,
+                depth: &mut usize,
+                cur_depth: usize,
+                settings: Option<GenerateSettings>,
+            ) -> Option<Self> {
+                let element_count = iterable_len(visitor, &settings);
+                let mut map = Self::new();
+                for _ in 0..element_count {
+                    let key = K::__autarkie_generate(visitor, &mut 0, cur_depth, None)?;
+                    let value = V::__autarkie_generate(visitor, &mut 0, cur_depth, None)?;
+                    // duplicate keys are dropped, so the map may be smaller
+                    map.entry(key).or_insert(value);
+                }
+                Some(map)
+            }
+
+            fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+                if !v.is_recursive(Self::inner_id()) {
+                    <(K, V)>::__autarkie_register(v, parent, variant);
+                } else {
+                    v.register_ty(parent, <(K, V)>::__autarkie_id_tuple(), variant);
+                    v.pop_ty();
+                }
+            }
+
+            fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
+                NodeType::Iterable(false, self.len(), Self::inner_id())
+            }
+
+            fn inner_id() -> Id {
+                <(K, V)>::__autarkie_id()
+            }
+
+            fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+                length_settings_bounds(settings)
+                    .map_or(true, |(min, max)| (min..=max).contains(&self.len()))
+            }
+
+            fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+                for (index, (key, value)) in self.iter().enumerate() {
+                    visitor.register_field_stack(((index, NodeType::NonRecursive), Self::inner_id()));
+                    visitor.register_field_stack(((0, key.__autarkie_node_ty(visitor)), K::__autarkie_id()));
+                    key.__autarkie_fields(visitor, 0);
+                    visitor.pop_field();
+                    visitor.register_field_stack(((1, value.__autarkie_node_ty(visitor)), V::__autarkie_id()));
+                    value.__autarkie_fields(visitor, 0);
+                    visitor.pop_field();
+                    visitor.pop_field();
+                }
+            }
+
+            fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+                for (index, (key, value)) in self.iter().enumerate() {
+                    visitor.register_field_stack(((index, NodeType::NonRecursive), Self::inner_id()));
+                    visitor.register_field_stack(((0, key.__autarkie_node_ty(visitor)), K::__autarkie_id()));
+                    key.__autarkie_cmps(visitor, 0, __autarkie_val);
+                    visitor.pop_field();
+                    visitor.register_field_stack(((1, value.__autarkie_node_ty(visitor)), V::__autarkie_id()));
+                    value.__autarkie_cmps(visitor, 1, __autarkie_val);
+                    visitor.pop_field();
+                    visitor.pop_field();
+                }
+            }
+
+            fn __autarkie_mutate(
+                &mut self,
+                ty: &mut MutationType,
+                visitor: &mut Visitor,
+                mut path: VecDeque<usize>,
+            ) {
+                if let Some(popped) = path.pop_front() {
+                    // we mutate the (k, v) entry, the key or the value.
+                    let key = self.keys().nth(popped).expect("XaLl1F31____").clone();
+                    let value = self.remove(&key).expect("WDZstzcR____");
+                    let mut entry = (key, value);
+                    let original = entry.clone();
+                    entry.__autarkie_mutate(ty, visitor, path);
+                    let (key, value) = if self.contains_key(&entry.0) {
+                        original
+                    } else {
+                        entry
+                    };
+                    self.insert(key, value);
+                } else {
+                    match ty {
+                        MutationType::Copy(buffer) => {
+                            **buffer = serialize(self);
+                        }
+                        MutationType::Splice(other) => {
+                            *self = deserialize(other);
+                        }
+                        MutationType::GenerateReplace(ref mut bias) => {
+                            if let Some(generated) = Self::__autarkie_generate(visitor, bias, 0, None) {
+                                *self = generated;
+                                visitor.add_serialized(serialize(self), Self::__autarkie_id());
+                                self.__autarkie_serialized(visitor);
+                            }
+                        }
+                        MutationType::SpliceAppend(other) => {
+                            let (key, value) = deserialize(other);
+                            self.entry(key).or_insert(value);
+                        }
+                        MutationType::GenerateAppend(bias) => {
+                            if let Some((key, value)) = <(K, V)>::__autarkie_generate(visitor, bias, 0, None) {
+                                self.entry(key).or_insert(value);
+                            }
+                        }
+                        MutationType::IterablePop(ref mut bias) => {
+                            let key = self.keys().nth(*bias).expect("2kejvSX9____").clone();
+                            self.remove(&key);
+                        }
+                        MutationType::Arithmetic(_) => {
+                            unreachable!("____6zsWxNfR")
+                        }
+                        MutationType::SwitchVariant(..) => {
+                            unreachable!("____Tf9Kd2Xh")
+                        }
+                        MutationType::Bytes(_) => {
+                            unreachable!("____b3kxMACI")
+                        }
+                        MutationType::Reorder(_) => {
+                            // the order is defined by the items themselves
+                        }
+                        MutationType::RecursiveReplace => {
+                            // replace the recursive keys and values with non-recursive ones
+                            for value in self.values_mut() {
+                                if value.__autarkie_node_ty(visitor).is_recursive() {
+                                    value.__autarkie_mutate(ty, visitor, VecDeque::new());
+                                }
+                            }
+                            let recursive = self
+                                .keys()
+                                .filter(|key| key.__autarkie_node_ty(visitor).is_recursive())
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            // one key at a time, so a colliding key can always go back in its place
+                            for key in recursive {
+                                let value = self.remove(&key).expect("Rq4nVd8L____");
+                                let mut replaced = key.clone();
+                                replaced.__autarkie_mutate(ty, visitor, VecDeque::new());
+                                if self.contains_key(&replaced) {
+                                    self.insert(key, value);
+                                } else {
+                                    self.insert(replaced, value);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            fn __autarkie_serialized(&self, visitor: &mut Visitor) {
+                for (key, value) in self {
+                    visitor.add_serialized(serialize(&(key, value)), Self::inner_id());
+                    visitor.add_serialized(serialize(key), K::__autarkie_id());
+                    key.__autarkie_serialized(visitor);
+                    visitor.add_serialized(serialize(value), V::__autarkie_id());
+                    value.__autarkie_serialized(visitor);
+                }
+            }
+        }
+    };
+}
+
+impl_node_map!(BTreeMap, Ord);
+#[cfg(not(feature = "scale"))]
+impl_node_map!(HashMap, Eq + Hash);
+
+/// Sets are iterables of their items, in iteration order.
+/// Mutations never merge two items, so no items are lost when they collide.
+macro_rules! impl_node_set {
+    ($set: ident, $($bound: tt)+) => {
+        impl<T> Node for $set<T>
+        where
+            T: Node + Clone + $($bound)+,
+        {
+            fn __autarkie_generate(
+                visitor: &mut Visitor,
+                depth: &mut usize,
+                cur_depth: usize,
+                settings: Option<GenerateSettings>,
+            ) -> Option<Self> {
+                let element_count = iterable_len(visitor, &settings);
+                let mut set = Self::new();
+                for _ in 0..element_count {
+                    // duplicates are dropped, so the set may be smaller
+                    set.insert(T::__autarkie_generate(visitor, &mut 0, cur_depth, None)?);
                 }
-                MutationType::GenerateAppend(bias) => {
-                    if let Some(k) = K::__autarkie_generate(visitor, bias, 0, None) {
-                        if let Some(v) = V::__autarkie_generate(visitor, bias, 0, None) {
-                            self.insert(k, v);
-                        }
+                Some(set)
+            }
+
+            fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+                <Vec<T>>::__autarkie_register(v, parent, variant);
+            }
+
+            fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
+                NodeType::Iterable(false, self.len(), Self::inner_id())
+            }
+
+            fn inner_id() -> Id {
+                T::__autarkie_id()
+            }
+
+            fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+                length_settings_bounds(settings)
+                    .map_or(true, |(min, max)| (min..=max).contains(&self.len()))
+            }
+
+            fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+                iterable_fields(self.iter(), visitor);
+            }
+
+            fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+                iterable_cmps(self.iter(), visitor, __autarkie_val);
+            }
+
+            fn __autarkie_mutate(
+                &mut self,
+                ty: &mut MutationType,
+                visitor: &mut Visitor,
+                mut path: VecDeque<usize>,
+            ) {
+                if let Some(popped) = path.pop_front() {
+                    let item = self.iter().nth(popped).expect("Hq3bTMZx____").clone();
+                    self.remove(&item);
+                    let mut mutated = item.clone();
+                    mutated.__autarkie_mutate(ty, visitor, path);
+                    if !self.insert(mutated) {
+                        self.insert(item);
                     }
-                }
-                MutationType::IterablePop(ref mut bias) => {
-                    let mut remove_key = None;
-                    for (i, (k, v)) in self.iter().enumerate() {
-                        if i == *bias {
-                            remove_key = Some(k.clone());
-                            break;
+                } else {
+                    match ty {
+                        MutationType::Copy(buffer) => {
+                            **buffer = serialize(self);
+                        }
+                        MutationType::Splice(other) => {
+                            *self = deserialize(other);
+                        }
+                        MutationType::GenerateReplace(ref mut bias) => {
+                            if let Some(generated) = Self::__autarkie_generate(visitor, bias, 0, None) {
+                                *self = generated;
+                                visitor.add_serialized(serialize(self), Self::__autarkie_id());
+                                self.__autarkie_serialized(visitor);
+                            }
+                        }
+                        MutationType::SpliceAppend(other) => {
+                            self.insert(deserialize(other));
+                        }
+                        MutationType::GenerateAppend(bias) => {
+                            if let Some(generated) = T::__autarkie_generate(visitor, bias, 0, None) {
+                                self.insert(generated);
+                            }
+                        }
+                        MutationType::IterablePop(ref mut bias) => {
+                            let item = self.iter().nth(*bias).expect("uW5CkEy7____").clone();
+                            self.remove(&item);
+                        }
+                        MutationType::Arithmetic(_) => {
+                            unreachable!("____Ztebo43t")
+                        }
+                        MutationType::SwitchVariant(..) => {
+                            unreachable!("____Gp5Wy7Rj")
+                        }
+                        MutationType::Bytes(_) => {
+                            unreachable!("____sErGT670")
+                        }
+                        MutationType::Reorder(_) => {
+                            // the order is defined by the items themselves
+                        }
+                        MutationType::RecursiveReplace => {
+                            // replace the recursive items with non-recursive ones
+                            let recursive = self
+                                .iter()
+                                .filter(|item| item.__autarkie_node_ty(visitor).is_recursive())
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            // one item at a time, so a colliding item can always go back in its place
+                            for item in recursive {
+                                self.remove(&item);
+                                let mut replaced = item.clone();
+                                replaced.__autarkie_mutate(ty, visitor, VecDeque::new());
+                                if !self.insert(replaced) {
+                                    self.insert(item);
+                                }
+                            }
                         }
                     }
-                    self.remove(&remove_key.expect("2kejvSX9____"))
-                        .expect("WDZstzcR____");
-                }
-                MutationType::RecursiveReplace => {
-                    // TODO
                 }
             }
+
+            fn __autarkie_serialized(&self, visitor: &mut Visitor) {
+                iterable_serialized(self.iter(), visitor);
+            }
         }
+    };
+}
+
+impl_node_set!(BTreeSet, Ord);
+#[cfg(not(feature = "scale"))]
+impl_node_set!(HashSet, Eq + Hash);
+
+/// Mutated as a Vec<T>
+impl<T> Node for VecDeque<T>
+where
+    T: Node,
+{
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        <Vec<T>>::__autarkie_generate(visitor, depth, cur_depth, settings).map(Self::from)
+    }
+
+    fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+        <Vec<T>>::__autarkie_register(v, parent, variant);
+    }
+
+    fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
+        NodeType::Iterable(false, self.len(), Self::inner_id())
+    }
+
+    fn inner_id() -> Id {
+        T::__autarkie_id()
+    }
+
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        length_settings_bounds(settings)
+            .map_or(true, |(min, max)| (min..=max).contains(&self.len()))
+    }
+
+    fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+        iterable_fields(self.iter(), visitor);
+    }
+
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        iterable_cmps(self.iter(), visitor, __autarkie_val);
+    }
+
+    fn __autarkie_mutate(
+        &mut self,
+        ty: &mut MutationType,
+        visitor: &mut Visitor,
+        path: VecDeque<usize>,
+    ) {
+        let mut items = Vec::from(std::mem::take(self));
+        items.__autarkie_mutate(ty, visitor, path);
+        *self = items.into();
+    }
+
+    fn __autarkie_serialized(&self, visitor: &mut Visitor) {
+        iterable_serialized(self.iter(), visitor);
+    }
+}
+
+/// Mutated as a Vec<T> in the heap's iteration order.
+impl<T> Node for BinaryHeap<T>
+where
+    T: Node + Ord,
+{
+    fn __autarkie_generate(
+        visitor: &mut Visitor,
+        depth: &mut usize,
+        cur_depth: usize,
+        settings: Option<GenerateSettings>,
+    ) -> Option<Self> {
+        <Vec<T>>::__autarkie_generate(visitor, depth, cur_depth, settings).map(Self::from)
+    }
+
+    fn __autarkie_register(v: &mut Visitor, parent: Option<(Id, String)>, variant: usize) {
+        <Vec<T>>::__autarkie_register(v, parent, variant);
+    }
+
+    fn __autarkie_node_ty(&self, visitor: &Visitor) -> NodeType {
+        NodeType::Iterable(false, self.len(), Self::inner_id())
+    }
+
+    fn inner_id() -> Id {
+        T::__autarkie_id()
+    }
+
+    fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+        length_settings_bounds(settings)
+            .map_or(true, |(min, max)| (min..=max).contains(&self.len()))
+    }
+
+    fn __autarkie_fields(&self, visitor: &mut Visitor, index: usize) {
+        iterable_fields(self.iter(), visitor);
+    }
+
+    fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+        iterable_cmps(self.iter(), visitor, __autarkie_val);
+    }
+
+    fn __autarkie_mutate(
+        &mut self,
+        ty: &mut MutationType,
+        visitor: &mut Visitor,
+        path: VecDeque<usize>,
+    ) {
+        // into_vec keeps the iteration order, so the path still points to the same item.
+        let mut items = std::mem::take(self).into_vec();
+        items.__autarkie_mutate(ty, visitor, path);
+        *self = items.into();
     }
 
     fn __autarkie_serialized(&self, visitor: &mut Visitor) {
-        for (k, v) in self {
-            visitor.add_serialized(serialize(&k), K::__autarkie_id());
-            k.__autarkie_serialized(visitor);
-            visitor.add_serialized(serialize(&v), V::__autarkie_id());
-            v.__autarkie_serialized(visitor);
+        iterable_serialized(self.iter(), visitor);
+    }
+}
+
+/// Apply a length preserving reordering to an iterable's items
+pub fn reorder_slice<T>(items: &mut [T], reorder: &Reorder, visitor: &mut Visitor) {
+    match reorder {
+        Reorder::Swap(a, b) => items.swap(*a, *b),
+        Reorder::Rotate(range, amount) => {
+            let slice = &mut items[range.clone()];
+            if !slice.is_empty() {
+                slice.rotate_left(*amount % slice.len());
+            }
+        }
+        Reorder::Reverse(range) => items[range.clone()].reverse(),
+        Reorder::Shuffle(range) => {
+            // fisher-yates
+            let slice = &mut items[range.clone()];
+            for i in (1..slice.len()).rev() {
+                slice.swap(i, visitor.random_range(0, i));
+            }
+        }
+        Reorder::Duplicate(_) => unreachable!("____p4TzWq8N"),
+    }
+}
+
+/// Register the fields of an iterable's items
+fn iterable_fields<'a, T: Node + 'a>(items: impl Iterator<Item = &'a T>, visitor: &mut Visitor) {
+    for (index, child) in items.enumerate() {
+        visitor.register_field_stack((
+            (index, child.__autarkie_node_ty(visitor)),
+            T::__autarkie_id(),
+        ));
+        child.__autarkie_fields(visitor, 0);
+        visitor.pop_field();
+    }
+}
+
+/// Find the cmps of an iterable's items
+fn iterable_cmps<'a, T: Node + 'a>(
+    items: impl Iterator<Item = &'a T>,
+    visitor: &mut Visitor,
+    __autarkie_val: CmpOperands,
+) {
+    for (index, child) in items.enumerate() {
+        visitor.register_field_stack((
+            (index, child.__autarkie_node_ty(visitor)),
+            T::__autarkie_id(),
+        ));
+        child.__autarkie_cmps(visitor, index, __autarkie_val);
+        visitor.pop_field();
+    }
+}
+
+/// Match the operands against an integer node of `bytes` bytes, as is or after a transformation.
+/// `value` is the node as u64, returns the replacements (to cast back into the node's type)
+/// with the transformation already undone.
+pub(crate) fn int_cmps(value: u64, bytes: u32, left: u64, right: u64) -> Vec<(u64, CmpTransform)> {
+    // wider nodes are compared through their low 8 bytes
+    let bits = bytes.min(8) * 8;
+    let mask = u64::MAX >> (64 - bits);
+    let swap = |value: u64| (value & mask).swap_bytes() >> (64 - bits);
+    let zero_extended = value & mask;
+    let sign_extended = (((zero_extended << (64 - bits)) as i64) >> (64 - bits)) as u64;
+    // `value` is already extended the way the node's type is
+    let extended = if value == zero_extended {
+        sign_extended
+    } else {
+        zero_extended
+    };
+    let mut replacements = vec![];
+    for (operand, other) in [(left, right), (right, left)] {
+        if value == operand {
+            replacements.push((other, CmpTransform::Identity));
+            replacements.push((other.wrapping_add(1), CmpTransform::OffByOne));
+            replacements.push((other.wrapping_sub(1), CmpTransform::OffByOne));
+            continue;
+        }
+        if (2..=8).contains(&bytes) && swap(value) == operand {
+            replacements.push((swap(other), CmpTransform::ByteSwap));
+        }
+        if extended != value && extended == operand {
+            replacements.push((other, CmpTransform::SignExtend));
         }
+        // small operands are close to too many nodes
+        let offset = operand.wrapping_sub(value) as i64;
+        if operand > u8::MAX as u64 && offset.unsigned_abs() <= CMP_MAX_OFFSET {
+            replacements.push((other.wrapping_sub(offset as u64), CmpTransform::Offset));
+        }
+    }
+    // writing back the node's own value would not change anything
+    replacements.retain(|(replacement, _)| replacement & mask != zero_extended);
+    replacements
+}
+
+/// Every copy of `data` with one occurrence of a byte operand replaced by the other operand
+pub(crate) fn byte_cmps(data: &[u8], left: &[u8], right: &[u8]) -> Vec<Vec<u8>> {
+    let mut replaced = vec![];
+    for (from, to) in [(left, right), (right, left)] {
+        if from.is_empty() || from == to || from.len() > data.len() {
+            continue;
+        }
+        for index in 0..=data.len() - from.len() {
+            if &data[index..index + from.len()] == from {
+                let mut alternative = data.to_vec();
+                alternative.splice(index..index + from.len(), to.iter().copied());
+                replaced.push(alternative);
+            }
+        }
+    }
+    replaced
+}
+
+/// Serialize an iterable's items for splicing
+fn iterable_serialized<'a, T: Node + 'a>(
+    items: impl Iterator<Item = &'a T>,
+    visitor: &mut Visitor,
+) {
+    for item in items {
+        visitor.add_serialized(serialize(item), T::__autarkie_id());
+        item.__autarkie_serialized(visitor);
     }
 }
 
@@ -971,6 +2169,9 @@ macro_rules! tuple_impls {
                     }
                 } else {
                     match ty {
+                        MutationType::Copy(buffer) => {
+                            **buffer = serialize(self);
+                        }
                         MutationType::Splice(other) => {
                             *self = deserialize(other);
                         },
@@ -1015,7 +2216,7 @@ macro_rules! tuple_impls {
                     })*
             }
 
-            fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
+            fn __autarkie_cmps(&self, visitor: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
                 $({
                 visitor.register_field_stack(((($id, crate::NodeType::NonRecursive)), $T::__autarkie_id()));
                 self.$id.__autarkie_cmps(visitor, 0, __autarkie_val);
@@ -1040,7 +2241,227 @@ tuple_impls! { (A , 0) ,(B, 1), (C, 2) ,(D, 3) ,(E, 4) ,(F, 5) ,(G, 6) ,(H, 7) ,
 tuple_impls! { (A , 0) ,(B, 1), (C, 2) ,(D, 3) ,(E, 4) ,(F, 5) ,(G, 6) ,(H, 7) ,(I, 8) ,(J, 9), (K, 10)}
 tuple_impls! { (A , 0) ,(B, 1), (C, 2) ,(D, 3) ,(E, 4) ,(F, 5) ,(G, 6) ,(H, 7) ,(I, 8) ,(J, 9) ,(K , 10) ,(L, 11)}
 
-macro_rules! impl_generate_simple {
+/// Clamp a range to valid lengths of an iterable
+fn length_bounds(range: &std::ops::RangeInclusive<i128>) -> (usize, usize) {
+    (
+        usize::try_from(*range.start()).unwrap_or(0),
+        usize::try_from(*range.end()).unwrap_or(usize::MAX),
+    )
+}
+
+/// How many items to generate for an iterable
+fn iterable_len(visitor: &mut Visitor, settings: &Option<GenerateSettings>) -> usize {
+    match settings {
+        Some(GenerateSettings::Length(len)) => *len,
+        Some(GenerateSettings::Range(range, _)) => {
+            let (min, max) = length_bounds(range);
+            // open ranges (eg: 2..) are as unbounded as iterables without settings
+            let max = max.min(min.saturating_add(visitor.iterate_depth()));
+            visitor.random_range(min, max)
+        }
+        _ => visitor.random_range(0, visitor.iterate_depth()),
+    }
+}
+
+/// The lengths an iterable may have according to its settings
+fn length_settings_bounds(settings: &GenerateSettings) -> Option<(usize, usize)> {
+    match settings {
+        GenerateSettings::Length(len) => Some((*len, *len)),
+        GenerateSettings::Range(range, _) => Some(length_bounds(range)),
+        _ => None,
+    }
+}
+
+/// How far from zero Distribution::Small strays
+const SMALL_VALUES: u128 = 16;
+
+/// Pick an offset in [0, span] according to the distribution.
+/// `zero` is the offset of the value closest to zero.
+fn sample_offset(v: &mut Visitor, span: u128, zero: u128, distribution: Distribution) -> u128 {
+    match distribution {
+        Distribution::Uniform => v.random_u128_inclusive(span),
+        Distribution::Boundary => {
+            let candidates = [
+                0,
+                1.min(span),
+                span.saturating_sub(1),
+                span,
+                zero,
+                zero.saturating_sub(1),
+                zero.saturating_add(1).min(span),
+            ];
+            candidates[v.random_range(0, candidates.len() - 1)]
+        }
+        Distribution::Small => {
+            let min = zero.saturating_sub(SMALL_VALUES);
+            let max = zero.saturating_add(SMALL_VALUES).min(span);
+            min + v.random_u128_inclusive(max - min)
+        }
+        Distribution::LogUniform => {
+            // pick a direction away from zero and then the bit width of the distance.
+            let up = span - zero;
+            let towards_max = zero == 0 || (up != 0 && v.coinflip());
+            let limit = if towards_max { up } else { zero };
+            let bits = v.random_range(0, (u128::BITS - limit.leading_zeros()) as usize);
+            let distance = if bits == 0 {
+                0
+            } else {
+                let min = 1 << (bits - 1);
+                let max = (u128::MAX >> (u128::BITS as usize - bits)).min(limit);
+                min + v.random_u128_inclusive(max - min)
+            };
+            if towards_max {
+                zero + distance
+            } else {
+                zero - distance
+            }
+        }
+    }
+}
+
+macro_rules! impl_generate_int {
+    ($type: ty, $num_bytes: literal) => {
+        impl Node for $type {
+            fn __autarkie_generate(
+                v: &mut Visitor,
+                depth: &mut usize,
+                cur_depth: usize,
+                settings: Option<GenerateSettings>,
+            ) -> Option<Self> {
+                let (range, distribution) = match settings {
+                    Some(GenerateSettings::Range(range, distribution)) => {
+                        (Some(range), distribution)
+                    }
+                    Some(GenerateSettings::Distribution(distribution)) => (None, distribution),
+                    _ => (None, Distribution::Uniform),
+                };
+                if distribution == Distribution::Uniform {
+                    static INTERESTING: OnceLock<Vec<$type>> = OnceLock::new();
+                    let table = INTERESTING.get_or_init(|| {
+                        let mut values = vec![
+                            0,
+                            1,
+                            (0 as Self).wrapping_sub(1),
+                            Self::MIN,
+                            Self::MAX,
+                            Self::MIN.wrapping_add(1),
+                            Self::MAX.wrapping_sub(1),
+                        ];
+                        // powers of two, their negations and their neighbours
+                        for bit in 1..Self::BITS {
+                            let power = (1 as Self) << bit;
+                            let negated = (0 as Self).wrapping_sub(power);
+                            values.extend([power, power.wrapping_sub(1), power.wrapping_add(1)]);
+                            values.extend([
+                                negated,
+                                negated.wrapping_sub(1),
+                                negated.wrapping_add(1),
+                            ]);
+                        }
+                        values.sort();
+                        values.dedup();
+                        values
+                    });
+                    let picked = v.pick_interesting(table, |harvested| harvested as Self);
+                    if let Some(picked) = picked {
+                        let in_range = range.as_ref().map_or(true, |range| {
+                            i128::try_from(picked).is_ok_and(|value| range.contains(&value))
+                        });
+                        if in_range {
+                            return Some(picked);
+                        }
+                    }
+                }
+                let (min, max) = match range {
+                    Some(range) => (
+                        Self::try_from(*range.start()).unwrap_or(if *range.start() < 0 {
+                            Self::MIN
+                        } else {
+                            Self::MAX
+                        }),
+                        Self::try_from(*range.end()).unwrap_or(if *range.end() < 0 {
+                            Self::MIN
+                        } else {
+                            Self::MAX
+                        }),
+                    ),
+                    None if distribution == Distribution::Uniform => {
+                        return Some(deserialize::<Self>(
+                            &mut v.generate_bytes($num_bytes).as_slice(),
+                        ))
+                    }
+                    None => (Self::MIN, Self::MAX),
+                };
+                if min > max {
+                    return None;
+                }
+                // we work with offsets from min so the whole range of u128 and i128 fits.
+                let span = (max as i128).wrapping_sub(min as i128) as u128;
+                let zero = if min >= (0 as Self) {
+                    0
+                } else if max <= (0 as Self) {
+                    span
+                } else {
+                    (min as i128).wrapping_neg() as u128
+                };
+                let offset = sample_offset(v, span, zero, distribution);
+                Some((min as i128).wrapping_add(offset as i128) as Self)
+            }
+            fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+                match settings {
+                    GenerateSettings::Range(range, _) => {
+                        i128::try_from(*self).is_ok_and(|value| range.contains(&value))
+                    }
+                    _ => true,
+                }
+            }
+            fn __autarkie_cmps(&self, v: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+                let CmpOperands::Int(left, right) = __autarkie_val else {
+                    return;
+                };
+                for (replacement, transform) in int_cmps(*self as u64, $num_bytes, left, right) {
+                    v.register_transformed_cmp(serialize(&(replacement as Self)), transform);
+                }
+            }
+            fn __autarkie_mutate(
+                &mut self,
+                ty: &mut MutationType,
+                v: &mut Visitor,
+                path: VecDeque<usize>,
+            ) {
+                debug_assert!(path.is_empty());
+                match ty {
+                    MutationType::Arithmetic(arithmetic) => {
+                        *self = match *arithmetic {
+                            Arithmetic::Add(delta) => self.wrapping_add(delta as Self),
+                            Arithmetic::Sub(delta) => self.wrapping_sub(delta as Self),
+                            Arithmetic::FlipBit(bit) => *self ^ ((1 as Self) << (bit % Self::BITS)),
+                            Arithmetic::SwapBytes => self.swap_bytes(),
+                            Arithmetic::Negate => self.wrapping_neg(),
+                        };
+                    }
+                    MutationType::Copy(buffer) => {
+                        **buffer = serialize(self);
+                    }
+                    MutationType::Splice(other) => {
+                        *self = deserialize(other);
+                    }
+                    MutationType::GenerateReplace(ref mut bias) => {
+                        if let Some(generated) = Self::__autarkie_generate(v, bias, 0, None) {
+                            *self = generated;
+                            v.add_serialized(serialize(self), Self::__autarkie_id());
+                        }
+                    }
+                    _ => {
+                        unreachable!()
+                    }
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_generate_float {
     ($type: ty, $num_bytes: literal) => {
         impl Node for $type {
             fn __autarkie_generate(
@@ -1049,43 +2470,178 @@ macro_rules! impl_generate_simple {
                 cur_depth: usize,
                 settings: Option<GenerateSettings>,
             ) -> Option<Self> {
-                let mut res = deserialize::<Self>(&mut v.generate_bytes($num_bytes).as_slice());
-                if let Some(GenerateSettings::Range(range)) = settings {
-                    res = res % (*range.end() as Self);
-                    if res < *range.start() as Self {
-                        res = (*range.start() as Self);
+                let (range, distribution) = match settings {
+                    Some(GenerateSettings::FloatRange(range, distribution)) => {
+                        (Some(range), distribution)
+                    }
+                    Some(GenerateSettings::Distribution(distribution)) => (None, distribution),
+                    _ => (None, Distribution::Uniform),
+                };
+                if distribution == Distribution::Uniform {
+                    let table = [
+                        0.0,
+                        -0.0,
+                        1.0,
+                        -1.0,
+                        Self::NAN,
+                        Self::INFINITY,
+                        Self::NEG_INFINITY,
+                        Self::MIN,
+                        Self::MAX,
+                        Self::MIN_POSITIVE,
+                        -Self::MIN_POSITIVE,
+                        Self::EPSILON,
+                        // subnormals
+                        Self::MIN_POSITIVE / 2.0,
+                        Self::from_bits(1),
+                    ];
+                    let picked = v.pick_interesting(&table, |harvested| harvested as Self);
+                    if let Some(picked) = picked {
+                        let in_range = range
+                            .as_ref()
+                            .map_or(true, |range| range.contains(&(picked as f64)));
+                        if in_range {
+                            return Some(picked);
+                        }
+                    }
+                }
+                let (min, max) = match &range {
+                    Some(range) => (
+                        range.start().max(Self::MIN as f64),
+                        range.end().min(Self::MAX as f64),
+                    ),
+                    None if distribution == Distribution::Uniform => {
+                        return Some(deserialize::<Self>(
+                            &mut v.generate_bytes($num_bytes).as_slice(),
+                        ))
+                    }
+                    None => (Self::MIN as f64, Self::MAX as f64),
+                };
+                if !(min <= max) {
+                    return None;
+                }
+                let uniform = |v: &mut Visitor, min: f64, max: f64| {
+                    let t = v.random_float();
+                    (min * (1.0 - t) + max * t).clamp(min, max)
+                };
+                let res = match distribution {
+                    Distribution::Uniform => uniform(v, min, max),
+                    Distribution::Boundary => {
+                        let mut candidates = vec![
+                            min,
+                            max,
+                            0.0,
+                            -0.0,
+                            1.0,
+                            -1.0,
+                            Self::EPSILON as f64,
+                            Self::MIN_POSITIVE as f64,
+                        ];
+                        candidates.retain(|c| (min..=max).contains(c));
+                        if range.is_none() {
+                            candidates.extend([f64::NAN, f64::INFINITY, f64::NEG_INFINITY]);
+                        }
+                        candidates[v.random_range(0, candidates.len() - 1)]
+                    }
+                    Distribution::Small => {
+                        let small = SMALL_VALUES as f64;
+                        if min > small || max < -small {
+                            uniform(v, min, max)
+                        } else {
+                            uniform(v, min.max(-small), max.min(small))
+                        }
+                    }
+                    Distribution::LogUniform => {
+                        // pick a direction away from zero and then the exponent of the distance.
+                        let towards_max = min >= 0.0 || (max > 0.0 && v.coinflip());
+                        let limit = if towards_max { max } else { -min };
+                        let max_exponent = limit.log2();
+                        let min_exponent = -(Self::MANTISSA_DIGITS as f64);
+                        let distance = if max_exponent > min_exponent {
+                            uniform(v, min_exponent, max_exponent).exp2()
+                        } else {
+                            limit.max(0.0)
+                        };
+                        let res = if towards_max { distance } else { -distance };
+                        res.clamp(min, max)
                     }
                 };
-                Some(res)
+                Some(res as Self)
             }
-            fn __autarkie_cmps(&self, v: &mut Visitor, index: usize, __autarkie_val: (u64, u64)) {
-                if __autarkie_val.0 == *self as u64 {
-                    v.register_cmp(serialize(&(__autarkie_val.1 as Self)));
-                } else if __autarkie_val.1 == *self as u64 {
-                    v.register_cmp(serialize(&(__autarkie_val.0 as Self)));
+            fn __autarkie_satisfies(&self, settings: &GenerateSettings) -> bool {
+                match settings {
+                    GenerateSettings::FloatRange(range, _) => range.contains(&(*self as f64)),
+                    _ => true,
+                }
+            }
+            fn __autarkie_cmps(&self, v: &mut Visitor, index: usize, __autarkie_val: CmpOperands) {
+                let CmpOperands::Int(left, right) = __autarkie_val else {
+                    return;
+                };
+                if left == *self as u64 {
+                    v.register_cmp(serialize(&(right as Self)));
+                } else if right == *self as u64 {
+                    v.register_cmp(serialize(&(left as Self)));
+                }
+            }
+            fn __autarkie_mutate(
+                &mut self,
+                ty: &mut MutationType,
+                v: &mut Visitor,
+                path: VecDeque<usize>,
+            ) {
+                debug_assert!(path.is_empty());
+                match ty {
+                    MutationType::Arithmetic(arithmetic) => {
+                        *self = match *arithmetic {
+                            Arithmetic::Add(delta) => *self + delta as Self,
+                            Arithmetic::Sub(delta) => *self - delta as Self,
+                            Arithmetic::FlipBit(bit) => {
+                                let bits = self.to_bits();
+                                Self::from_bits(bits ^ (1 << (bit % ($num_bytes * 8))))
+                            }
+                            Arithmetic::SwapBytes => Self::from_bits(self.to_bits().swap_bytes()),
+                            Arithmetic::Negate => -*self,
+                        };
+                    }
+                    MutationType::Copy(buffer) => {
+                        **buffer = serialize(self);
+                    }
+                    MutationType::Splice(other) => {
+                        *self = deserialize(other);
+                    }
+                    MutationType::GenerateReplace(ref mut bias) => {
+                        if let Some(generated) = Self::__autarkie_generate(v, bias, 0, None) {
+                            *self = generated;
+                            v.add_serialized(serialize(self), Self::__autarkie_id());
+                        }
+                    }
+                    _ => {
+                        unreachable!()
+                    }
                 }
             }
         }
     };
 }
 
-impl_generate_simple!(f32, 4);
-impl_generate_simple!(f64, 8);
-
-impl_generate_simple!(u8, 1);
-impl_generate_simple!(u16, 2);
-impl_generate_simple!(u32, 4);
-impl_generate_simple!(u64, 8);
-impl_generate_simple!(u128, 32);
-impl_generate_simple!(i8, 1);
-impl_generate_simple!(i16, 2);
-impl_generate_simple!(i32, 4);
-impl_generate_simple!(i64, 8);
-impl_generate_simple!(i128, 32);
+impl_generate_float!(f32, 4);
+impl_generate_float!(f64, 8);
+
+impl_generate_int!(u8, 1);
+impl_generate_int!(u16, 2);
+impl_generate_int!(u32, 4);
+impl_generate_int!(u64, 8);
+impl_generate_int!(u128, 16);
+impl_generate_int!(i8, 1);
+impl_generate_int!(i16, 2);
+impl_generate_int!(i32, 4);
+impl_generate_int!(i64, 8);
+impl_generate_int!(i128, 16);
 #[cfg(not(feature = "scale"))]
-impl_generate_simple!(isize, 8);
+impl_generate_int!(isize, 8);
 #[cfg(not(feature = "scale"))]
-impl_generate_simple!(usize, 8);
+impl_generate_int!(usize, 8);
 
 #[cfg(not(feature = "scale"))]
 pub fn serialize<T>(data: &T) -> Vec<u8>
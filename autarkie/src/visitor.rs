@@ -1,4 +1,4 @@
-use crate::Id;
+use crate::{regex_sampler::RegexSampler, CmpTransform, Id, Node};
 use libafl_bolts::rands::{Rand, StdRand};
 use num_traits::CheckedSub;
 use petgraph::{
@@ -23,12 +23,16 @@ pub struct Visitor {
     depth: DepthInfo,
     /// Pool of strings the fuzzer uses.
     strings: StringPool,
+    /// Integer constants harvested by cmplog, mixed into the generation of numbers.
+    harvested_ints: Vec<u64>,
+    /// The probability of generating a number from its interesting values.
+    interesting_probability: f64,
     /// The list of fields inside a Fuzz-ed type's Instance
     fields: Vec<Vec<FieldLocation>>,
     /// The stack of fields inside a Fuzz-ed type's Instance.
     field_stack: Vec<FieldLocation>,
     /// For cmplog, we map fields which match the bytes provided
-    matching_cmps: Vec<(Vec<FieldLocation>, Vec<u8>)>,
+    matching_cmps: Vec<(Vec<FieldLocation>, Vec<u8>, CmpTransform)>,
     /// A map of types which are mapped to their variants and their fields.
     /// Examples:
     /// a struct will be { Struct: {0: { usize, u32 } } }
@@ -42,6 +46,22 @@ pub struct Visitor {
     /// Fields which are serialized by the Fuzz-ed type's instance. Used to save to corpora for splicing
     serialized: Vec<(Vec<u8>, Id)>,
     ty_generate_map: BTreeMap<Id, BTreeMap<GenerateType, BTreeSet<usize>>>,
+    /// Weights of enum variants, set using #[autarkie_weight(N)].
+    /// Variants which are not present have a weight of 1.
+    ty_weight_map: BTreeMap<Id, BTreeMap<usize, usize>>,
+    /// Variants disabled by a grammar tuning file.
+    /// They are never generated and inputs containing them are discarded.
+    ty_disabled_map: BTreeMap<Id, BTreeSet<usize>>,
+    /// Regexes of #[autarkie_regex(..)] fields.
+    regexes: BTreeMap<&'static str, RegexSampler>,
+    /// Whether any field has constraints, see `Visitor::violates_constraints`
+    has_field_constraints: bool,
+    /// Whether we are checking an input's constraints, see `Visitor::violates_constraints`
+    checking_constraints: bool,
+    /// Whether we visited a disabled variant or a field which does not match its regex
+    violated_constraint: bool,
+    /// Enums visited by `__autarkie_fields`, see `Visitor::enums`
+    enums: Vec<EnumLocation>,
     /// State of randomnes
     rng: StdRand,
     has_recursive_types: bool,
@@ -55,6 +75,52 @@ impl Visitor {
         self.strings.register_string(string)
     }
 
+    /// Count a byte operand of a comparison (eg: from cmplog) as a token, returns whether it is new
+    pub fn register_token(&mut self, token: &[u8]) -> bool {
+        self.strings.tokens.register_token(token, 1)
+    }
+
+    /// Tokens harvested from comparisons, see `TokenPool`
+    pub fn tokens(&self) -> &TokenPool {
+        &self.strings.tokens
+    }
+
+    /// Add the tokens we do not know yet from another pool (eg: of another core), returns them
+    pub fn merge_tokens(&mut self, other: &TokenPool) -> Vec<Vec<u8>> {
+        self.strings.tokens.merge(other)
+    }
+
+    /// Add an integer constant (eg: from cmplog) to the interesting values of numbers
+    pub fn register_interesting(&mut self, value: u64) {
+        if self.harvested_ints.len() < MAX_HARVESTED_INTS && !self.harvested_ints.contains(&value) {
+            self.harvested_ints.push(value);
+        }
+    }
+
+    pub fn set_interesting_probability(&mut self, probability: f64) {
+        self.interesting_probability = probability;
+    }
+
+    /// With the interesting probability, pick a value from a type's table or the harvested constants.
+    pub fn pick_interesting<T: Copy>(
+        &mut self,
+        table: &[T],
+        from_harvested: impl Fn(u64) -> T,
+    ) -> Option<T> {
+        if !self.rng.coinflip(self.interesting_probability) {
+            return None;
+        }
+        let total = table.len() + self.harvested_ints.len();
+        if total == 0 {
+            return None;
+        }
+        let index = self.rng.between(0, total - 1);
+        match table.get(index) {
+            Some(value) => Some(*value),
+            None => Some(from_harvested(self.harvested_ints[index - table.len()])),
+        }
+    }
+
     pub fn generate_bytes(&mut self, amount: usize) -> Vec<u8> {
         // TODO: possible to make more efficient?
         (0..amount)
@@ -74,6 +140,28 @@ impl Visitor {
         self.rng.between(min, max)
     }
 
+    /// A random number in [0, max], without modulo bias.
+    pub fn random_u128_inclusive(&mut self, max: u128) -> u128 {
+        let mut next = || ((self.rng.next() as u128) << 64) | self.rng.next() as u128;
+        if max == u128::MAX {
+            return next();
+        }
+        let count = max + 1;
+        // the largest multiple of count we can represent, so every value is equally likely
+        let zone = u128::MAX - (u128::MAX % count + 1) % count;
+        loop {
+            let res = next();
+            if res <= zone {
+                return res % count;
+            }
+        }
+    }
+
+    /// A random float in [0, 1)
+    pub fn random_float(&mut self) -> f64 {
+        self.rng.next_float()
+    }
+
     pub fn set_seed(&mut self, seed: u64) {
         self.rng.set_seed(seed);
     }
@@ -84,7 +172,25 @@ impl Visitor {
     }
 
     pub fn register_cmp(&mut self, data: Vec<u8>) {
-        self.matching_cmps.push((self.field_stack.clone(), data));
+        self.register_transformed_cmp(data, CmpTransform::Identity);
+    }
+
+    /// Register a replacement which matched the comparison after a transformation
+    pub fn register_transformed_cmp(&mut self, data: Vec<u8>, transform: CmpTransform) {
+        self.matching_cmps
+            .push((self.field_stack.clone(), data, transform));
+    }
+
+    pub fn cmps_len(&self) -> usize {
+        self.matching_cmps.len()
+    }
+
+    /// Drop the cmps registered after `from` which do not satisfy the filter.
+    /// Used by fields with constraints (eg: #[autarkie_range(..)])
+    pub fn retain_cmps(&mut self, from: usize, filter: impl Fn(&[u8]) -> bool) {
+        let registered = self.matching_cmps.split_off(from);
+        self.matching_cmps
+            .extend(registered.into_iter().filter(|(_, data, _)| filter(data)));
     }
 
     pub fn register_field_stack(&mut self, item: FieldLocation) {
@@ -96,18 +202,34 @@ impl Visitor {
     }
 
     pub fn cmps(&mut self) -> Vec<(Vec<FieldLocation>, Vec<u8>)> {
+        self.transformed_cmps()
+            .into_iter()
+            .map(|(path, data, _)| (path, data))
+            .collect()
+    }
+
+    /// Like `cmps`, with the transformation each replacement matched with
+    pub fn transformed_cmps(&mut self) -> Vec<(Vec<FieldLocation>, Vec<u8>, CmpTransform)> {
         let cmps = std::mem::take(&mut self.matching_cmps);
         self.fields.clear();
         self.field_stack.clear();
+        self.enums.clear();
         cmps
     }
 
     pub fn fields(&mut self) -> Vec<Vec<FieldLocation>> {
         let fields = std::mem::take(&mut self.fields);
         self.field_stack.clear();
+        self.enums.clear();
         fields
     }
 
+    /// The enums (and Options) with more than one variant, registered by `__autarkie_fields`.
+    /// Must be taken before `Visitor::fields`, which clears them.
+    pub fn enums(&mut self) -> Vec<EnumLocation> {
+        std::mem::take(&mut self.enums)
+    }
+
     pub fn add_serialized(&mut self, serialized_data: Vec<u8>, id: Id) {
         self.serialized.push((serialized_data, id))
     }
@@ -148,6 +270,120 @@ impl Visitor {
             .or_insert(BTreeSet::from_iter([id.0.clone()]));
     }
 
+    /// Set the weight of an enum's variant. A weight of 0 means the variant is never generated.
+    pub fn register_weight(&mut self, id: Id, variant: usize, weight: usize) {
+        self.ty_weight_map
+            .entry(id)
+            .or_default()
+            .insert(variant, weight);
+    }
+
+    /// Apply a grammar tuning file.
+    /// Types are referenced by their name in the `ty_name_map`.
+    /// Must be called after `Visitor::calculate_recursion`
+    pub fn apply_tuning(&mut self, tuning: GrammarTuning) -> Result<(), String> {
+        for (name, ty_tuning) in tuning {
+            let Some((id, _)) = self.ty_name_map.iter().find(|(_, n)| **n == name) else {
+                return Err(format!("unknown type {name:?} in tuning file"));
+            };
+            let id = *id;
+            if !self.ty_generate_map.contains_key(&id) {
+                return Err(format!("type {name:?} in tuning file has no variants"));
+            }
+            let variant_count = self.variant_count(&id);
+            for variant in ty_tuning.weights.keys().chain(ty_tuning.disable.iter()) {
+                if *variant >= variant_count {
+                    return Err(format!(
+                        "type {name:?} has no variant {variant} (it has {variant_count})"
+                    ));
+                }
+            }
+            for (variant, weight) in ty_tuning.weights {
+                self.register_weight(id, variant, weight);
+            }
+            for variant in ty_tuning.disable {
+                self.register_weight(id, variant, 0);
+                self.ty_disabled_map.entry(id).or_default().insert(variant);
+            }
+        }
+        Ok(())
+    }
+
+    /// Used by enums when visiting their fields to check for disabled variants.
+    pub fn visit_variant(&mut self, id: Id, variant: usize) {
+        if !self.checking_constraints {
+            // enums with a single variant have nothing to switch to
+            if self.variant_count(&id) > 1 {
+                let path = self.field_stack.iter().map(|((index, _), _)| *index);
+                self.enums.push((path.collect(), id, variant));
+            }
+            return;
+        }
+        if self
+            .ty_disabled_map
+            .get(&id)
+            .is_some_and(|disabled| disabled.contains(&variant))
+        {
+            self.violated_constraint = true;
+        }
+    }
+
+    /// Used by fields with constraints (eg: #[autarkie_regex(..)]) when visiting their fields to
+    /// check whether they still hold.
+    pub fn visit_constraint(&mut self, check: impl FnOnce(&Self) -> bool) {
+        if !self.checking_constraints {
+            return;
+        }
+        if !check(self) {
+            self.violated_constraint = true;
+        }
+    }
+
+    /// Whether inputs have to be checked using `Visitor::violates_constraints`
+    pub fn has_constraints(&self) -> bool {
+        !self.ty_disabled_map.is_empty() || self.has_field_constraints
+    }
+
+    /// Used by fields with constraints when registering.
+    pub fn register_constraint(&mut self) {
+        self.has_field_constraints = true;
+    }
+
+    /// Whether the node contains a variant which was disabled by a grammar tuning file
+    /// or a field which violates its constraints (eg: #[autarkie_regex(..)] or #[autarkie_range(..)])
+    pub fn violates_constraints<T: Node>(&mut self, node: &T) -> bool {
+        if !self.has_constraints() {
+            return false;
+        }
+        self.checking_constraints = true;
+        self.violated_constraint = false;
+        node.__autarkie_fields(self, 0);
+        let _ = self.fields();
+        self.checking_constraints = false;
+        std::mem::take(&mut self.violated_constraint)
+    }
+
+    /// Used by #[autarkie_regex(..)] fields when registering.
+    pub fn register_regex(&mut self, pattern: &'static str) {
+        self.register_constraint();
+        self.regexes
+            .entry(pattern)
+            .or_insert_with(|| RegexSampler::new(pattern));
+    }
+
+    /// Generate a string for a #[autarkie_regex(..)] field.
+    pub fn generate_regex(&mut self, pattern: &'static str) -> String {
+        let sampler = self.regexes.get(pattern).expect("cS8mXq2T____");
+        sampler.generate(&mut self.rng, self.depth.iterate)
+    }
+
+    pub fn matches_regex(&self, pattern: &'static str, value: &str) -> bool {
+        self.regexes
+            .get(pattern)
+            .expect("Vt5nR0dW____")
+            .is_match(value)
+    }
+
     pub fn pop_ty(&mut self) {
         let popped = self.ty_map_stack.pop().expect("____mZiIy3hlu8");
         self.ty_done.insert(popped);
@@ -270,9 +506,11 @@ impl Visitor {
     /// If not, we MAY NOT pick a recursive variant
     /// If we do not have any non-recursive variants we return None and the Input
     /// generation/mutation fails.
+    /// Variants are picked proportionally to their weight (see #[autarkie_weight(N)]).
     pub fn generate(&mut self, id: &Id, depth: usize) -> Option<(usize, bool)> {
         let consider_recursive = depth < self.depth.generate;
         let variants = self.ty_generate_map.get(&id).expect("pxc9jCnK____");
+        let weights = self.ty_weight_map.get(&id);
         if consider_recursive {
             let nr_variants = variants
                 .get(&GenerateType::NonRecursive)
@@ -280,12 +518,10 @@ impl Visitor {
             let r_variants = variants
                 .get(&GenerateType::Recursive)
                 .expect("____q154Wl5zf2");
-            let ret = self
-                .rng
-                .choose(nr_variants.iter().chain(r_variants))
-                .expect("O3pQMbj8____");
-            let is_recursive = r_variants.contains(ret);
-            Some((ret.clone(), is_recursive))
+            let ret =
+                choose_weighted(&mut self.rng, weights, nr_variants.iter().chain(r_variants))?;
+            let is_recursive = r_variants.contains(&ret);
+            Some((ret, is_recursive))
         } else {
             let nr_variants = variants
                 .get(&GenerateType::NonRecursive)
@@ -293,10 +529,25 @@ impl Visitor {
             if nr_variants.len() == 0 {
                 return None;
             }
-            let ret = self.rng.choose(nr_variants.iter()).expect("eCdWPiyf____");
-            Some((ret.clone(), false))
+            let ret = choose_weighted(&mut self.rng, weights, nr_variants.iter())?;
+            Some((ret, false))
         }
     }
+    /// Whether the variant may be generated, ie: its weight is not 0
+    pub fn is_variant_enabled(&self, id: &Id, variant: usize) -> bool {
+        self.ty_weight_map
+            .get(id)
+            .and_then(|weights| weights.get(&variant))
+            .map_or(true, |weight| *weight > 0)
+    }
+
+    /// The amount of variants of a type, 0 if it is not registered
+    pub fn variant_count(&self, id: &Id) -> usize {
+        self.ty_generate_map
+            .get(id)
+            .map_or(0, |variants| variants.values().map(|v| v.len()).sum())
+    }
+
     pub fn ty_name_map(&self) -> &BTreeMap<Id, String> {
         &self.ty_name_map
     }
@@ -311,6 +562,13 @@ impl Visitor {
         let mut visitor = Self {
             has_recursive_types: false,
             ty_generate_map: BTreeMap::default(),
+            ty_weight_map: BTreeMap::default(),
+            ty_disabled_map: BTreeMap::default(),
+            regexes: BTreeMap::default(),
+            has_field_constraints: false,
+            checking_constraints: false,
+            violated_constraint: false,
+            enums: vec![],
             ty_name_map: BTreeMap::default(),
             ty_done: BTreeSet::default(),
             ty_map_stack: vec![],
@@ -320,6 +578,8 @@ impl Visitor {
             matching_cmps: vec![],
             serialized: vec![],
             strings: StringPool::new(),
+            harvested_ints: vec![],
+            interesting_probability: DEFAULT_INTERESTING_PROBABILITY,
             ty_map: BTreeMap::new(),
             rng: StdRand::with_seed(seed),
         };
@@ -330,6 +590,48 @@ impl Visitor {
     }
 }
 
+/// A grammar tuning file, mapping type names (see `type_input_map.json`) to their tuning.
+pub type GrammarTuning = BTreeMap<String, TypeTuning>;
+
+/// Runtime overrides for an enum's variants.
+/// Variant indices are the same as in `type_generate_map.json`
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct TypeTuning {
+    /// Variants which are never generated
+    #[serde(default)]
+    pub disable: BTreeSet<usize>,
+    /// Overrides the variants' #[autarkie_weight(N)]
+    #[serde(default)]
+    pub weights: BTreeMap<usize, usize>,
+}
+
+/// Pick a variant with a probability proportional to its weight.
+/// If the type has no weights we fall back to a uniform choice.
+/// Returns None if every variant has a weight of 0.
+fn choose_weighted<'a>(
+    rng: &mut StdRand,
+    weights: Option<&BTreeMap<usize, usize>>,
+    variants: impl Iterator<Item = &'a usize> + Clone,
+) -> Option<usize> {
+    let Some(weights) = weights else {
+        return rng.choose(variants).cloned();
+    };
+    let weight_of = |variant: &usize| *weights.get(variant).unwrap_or(&1);
+    let total = variants.clone().map(weight_of).sum::<usize>();
+    if total == 0 {
+        return None;
+    }
+    let mut pick = rng.between(0, total - 1);
+    for variant in variants {
+        let weight = weight_of(variant);
+        if pick < weight {
+            return Some(*variant);
+        }
+        pick -= weight;
+    }
+    unreachable!("I3kqZc0e____")
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub enum NodeType {
     ///  A normal node
@@ -357,6 +659,17 @@ impl NodeType {
     }
 }
 
+/// The default probability of generating a number from its interesting values
+pub const DEFAULT_INTERESTING_PROBABILITY: f64 = 0.1;
+/// We stop harvesting cmplog constants after this many
+const MAX_HARVESTED_INTS: usize = 4096;
+/// The amount of tokens harvested from comparisons we keep, the lowest ranked are evicted
+pub const MAX_AUTO_TOKENS: usize = 1024;
+/// Longer byte operands are rarely keywords or magic values
+pub const MAX_AUTO_TOKEN_LEN: usize = 64;
+/// The probability of generating a String from the harvested tokens
+pub const AUTO_TOKEN_PROBABILITY: f64 = 0.2;
+
 #[derive(Debug, Clone)]
 /// The DepthInfo struct throttles the generation and mutation of inputs.
 /// We need to set a recursive depth on Inputs so self referencing types do not result in a stack overflow
@@ -375,23 +688,38 @@ pub enum GenerateType {
 }
 
 pub type FieldLocation = ((usize, NodeType), Id);
+/// The path to an enum, its type and its current variant
+pub type EnumLocation = (Vec<usize>, Id, usize);
 
 /// Pool of Strings used by the fuzzer
 #[derive(Debug, Clone)]
 pub struct StringPool {
     strings: Vec<String>,
+    /// Tokens harvested from comparisons
+    tokens: TokenPool,
 }
 
 impl StringPool {
-    /// Fetch a random string from the string pool
+    /// Fetch a random string from the string pool, or one of the harvested tokens
     pub fn get_string(&mut self, r: &mut StdRand) -> String {
+        // only consume randomness for the tokens once we harvested some
+        if !self.tokens.is_empty() && r.coinflip(AUTO_TOKEN_PROBABILITY) {
+            if let Some(token) = self.tokens.get_token(r) {
+                if let Ok(token) = std::str::from_utf8(token) {
+                    return token.to_string();
+                }
+            }
+        }
         let string_count = self.strings.len() - 1;
         let index = r.between(0, string_count);
         self.strings.get(index).expect("5hxil4dq____").clone()
     }
 
     pub fn new() -> Self {
-        Self { strings: vec![] }
+        Self {
+            strings: vec![],
+            tokens: TokenPool::default(),
+        }
     }
 
     /// Add a string manually
@@ -417,3 +745,87 @@ impl StringPool {
         }
     }
 }
+
+/// Byte operands of comparisons the target made, deduplicated and ranked by how often it compared them.
+/// Persisted to `auto_tokens.json` in the fuzzer's directory and shared between cores.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TokenPool {
+    /// Tokens and their hits, highest ranked first
+    tokens: Vec<(Vec<u8>, usize)>,
+}
+
+impl TokenPool {
+    /// Add `hits` to a token, returns whether it is new.
+    /// If the pool is full, a new token evicts the lowest ranked one unless that one has more hits
+    pub fn register_token(&mut self, token: &[u8], hits: usize) -> bool {
+        self.insert(token, hits, |known, hits| known + hits)
+    }
+
+    /// Like `register_token`, but a known token's hits are combined with `combine`
+    fn insert(&mut self, token: &[u8], hits: usize, combine: fn(usize, usize) -> usize) -> bool {
+        if token.is_empty() || token.len() > MAX_AUTO_TOKEN_LEN || token.iter().all(|b| *b == 0) {
+            return false;
+        }
+        let (mut index, new) = match self.tokens.iter().position(|(known, _)| known == token) {
+            Some(index) => {
+                self.tokens[index].1 = combine(self.tokens[index].1, hits);
+                (index, false)
+            }
+            None => {
+                if self.tokens.len() >= MAX_AUTO_TOKENS {
+                    if self.tokens.last().expect("hT4kq0Zr____").1 > hits {
+                        return false;
+                    }
+                    self.tokens.pop();
+                }
+                self.tokens.push((token.to_vec(), hits));
+                (self.tokens.len() - 1, true)
+            }
+        };
+        // keep the ranking
+        while index > 0 && self.tokens[index - 1].1 < self.tokens[index].1 {
+            self.tokens.swap(index - 1, index);
+            index -= 1;
+        }
+        new
+    }
+
+    /// Add the other's tokens with their hits, returns the ones we did not know yet.
+    /// Known tokens keep the highest of both hits, so merging the same pool again changes nothing.
+    /// An empty pool (eg: on restart) takes over the other's ranking
+    pub fn merge(&mut self, other: &TokenPool) -> Vec<Vec<u8>> {
+        if self.tokens.is_empty() {
+            self.tokens = other.tokens.clone();
+            return self.iter().map(<[u8]>::to_vec).collect();
+        }
+        other
+            .tokens
+            .iter()
+            .filter(|(token, hits)| self.insert(token, *hits, usize::max))
+            .map(|(token, _)| token.clone())
+            .collect()
+    }
+
+    /// Pick a token, favouring the higher ranked ones
+    pub fn get_token(&self, r: &mut StdRand) -> Option<&[u8]> {
+        if self.tokens.is_empty() {
+            return None;
+        }
+        let first = r.between(0, self.tokens.len() - 1);
+        let second = r.between(0, self.tokens.len() - 1);
+        Some(&self.tokens[first.min(second)].0)
+    }
+
+    /// The tokens, highest ranked first
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.tokens.iter().map(|(token, _)| token.as_slice())
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
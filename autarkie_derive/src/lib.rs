@@ -1,15 +1,28 @@
 extern crate proc_macro2;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 mod trait_bounds;
 mod utils;
 use syn::{spanned::Spanned, token::Comma, *};
 
-#[proc_macro_derive(Grammar, attributes(autarkie_literal, autarkie_length, autarkie_range))]
+#[proc_macro_derive(
+    Grammar,
+    attributes(
+        autarkie_literal,
+        autarkie_length,
+        autarkie_range,
+        autarkie_weight,
+        autarkie_with,
+        autarkie_regex,
+        autarkie_distribution,
+        autarkie
+    )
+)]
 pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut base_parsed = syn::parse_macro_input!(input as syn::DeriveInput);
     let root_name = &base_parsed.ident;
+    let remote = construct_remote(&base_parsed);
     let expanded = match base_parsed.data {
         Data::Struct(ref data) => {
             let fields = get_fields(&data.fields);
@@ -17,15 +30,16 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             let parsed = parse_fields(fields);
             let generate = construct_generate_function_struct(&parsed, is_named);
 
-            let serialized_inner = parsed.iter().map(|field| {
+            let serialized_inner = parsed.iter().filter(|field| field.is_spliceable()).map(|field| {
                 let name = field.get_name(is_named);
-                let ty = &field.ty;
+                let ty = field.grammar_ty();
+                let value = field.grammar_value(quote! {self.#name});
                 quote! {
                         // todo: check fixed size
-                        if !matches!(self.#name.__autarkie_node_ty(autarkie_visitor), autarkie::visitor::NodeType::Iterable(..)) {
-                            autarkie_visitor.add_serialized(::autarkie::serialize(&self.#name), <#ty>::__autarkie_id());
+                        if !matches!(#value.__autarkie_node_ty(autarkie_visitor), autarkie::visitor::NodeType::Iterable(..)) {
+                            autarkie_visitor.add_serialized(::autarkie::serialize(&#value), <#ty>::__autarkie_id());
                         }
-                        self.#name.__autarkie_serialized(autarkie_visitor);
+                        #value.__autarkie_serialized(autarkie_visitor);
                 }
             });
 
@@ -34,31 +48,45 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 #(#serialized_inner)*
             };
 
-            let register_field = parsed.iter().map(|field| {
-                let id = &field.id;
-                let ty = &field.ty;
-                let name = field.get_name(is_named);
-                quote! {
-                    v.register_field(((#id, self.#name.__autarkie_node_ty(v)), <#ty>::__autarkie_id()));
-                    self.#name.__autarkie_fields(v, 0);
-                    v.pop_field();
-                }
-            });
-            let register_cmps = parsed.iter().map(|field| {
+            let register_field = parsed
+                .iter()
+                .filter(|field| !field.is_skipped())
+                .map(|field| {
+                    let id = &field.id;
+                    let ty = field.grammar_ty();
+                    let name = field.get_name(is_named);
+                    let constraints = construct_constraint_checks(field, quote! {self.#name});
+                    if !field.is_spliceable() {
+                        return constraints;
+                    }
+                    let (node_ty, inner) =
+                        construct_field_visit(field, quote! {self.#name}, quote! {0});
+                    quote! {
+                        v.register_field(((#id, #node_ty), <#ty>::__autarkie_id()));
+                        #constraints
+                        #inner
+                        v.pop_field();
+                    }
+                });
+            let register_cmps = parsed.iter().filter(|field| field.is_spliceable()).map(|field| {
                 let id = &field.id;
-                let ty = &field.ty;
+                let ty = field.grammar_ty();
                 let name = field.get_name(is_named);
+                let value = field.grammar_value(quote! {self.#name});
+                let cmps = construct_cmps(field, quote! {self.#name}, quote! {0});
                 quote! {
-                    v.register_field(((#id, self.#name.__autarkie_node_ty(v)), <#ty>::__autarkie_id()));
-                    self.#name.__autarkie_cmps(v, 0, __autarkie_val);
+                    v.register_field(((#id, #value.__autarkie_node_ty(v)), <#ty>::__autarkie_id()));
+                    #cmps
                     v.pop_field();
 
                 }
             });
 
-            let register_ty = parsed.iter().map(|field| {
-                let ty = &field.ty;
+            let register_ty = parsed.iter().filter(|field| !field.is_skipped()).map(|field| {
+                let ty = field.grammar_ty();
+                let constraints = construct_register_constraints(field);
                 quote! {
+                    #constraints
                     if !v.is_recursive(<#ty>::__autarkie_id()) {
                         <#ty>::__autarkie_register(v, Some(Self::__autarkie_id_tuple()), 0);
                     } else {
@@ -68,15 +96,13 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 }
             });
 
-            let inner_mutate = parsed.iter().map(|field| {
-                let id = &field.id;
-                let name = field.get_name(is_named);
-                quote! {
-                    #id => {
-                        self.#name.__autarkie_mutate(autarkie_ty, autarkie_visitor, autarkie_path);
-                    },
-                }
-            });
+            let inner_mutate = parsed
+                .iter()
+                .filter(|field| field.is_spliceable())
+                .map(|field| {
+                    let name = field.get_name(is_named);
+                    construct_mutate_arm(field, quote! {self.#name})
+                });
             trait_bounds::add(root_name, &mut base_parsed.generics, &base_parsed.data);
             let (impl_generics, ty_generics, where_clause) = base_parsed.generics.split_for_impl();
             // Generate the Node trait implementation for the Struct
@@ -98,7 +124,7 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     }
 
 
-                    fn __autarkie_cmps(&self, v: &mut ::autarkie::Visitor, __autarkie_index: usize, __autarkie_val: (u64, u64)) {
+                    fn __autarkie_cmps(&self, v: &mut ::autarkie::Visitor, __autarkie_index: usize, __autarkie_val: ::autarkie::CmpOperands) {
                         #(#register_cmps)*
                     }
 
@@ -120,6 +146,9 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                             }
                         } else {
                             match autarkie_ty {
+                                autarkie::MutationType::Copy(buffer) => {
+                                    **buffer = autarkie::serialize(self);
+                                }
                                 autarkie::MutationType::Splice(other) => {
                                     *self = autarkie::deserialize(other);
                                 }
@@ -136,7 +165,7 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                             }
                         }
                     }
-                };
+                }
             };
 
             quote! {
@@ -150,12 +179,41 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             let mut fn_cmps = vec![];
             let mut are_we_recursive = vec![];
             let mut register_ty = vec![];
+            let mut register_weight = vec![];
             let mut serialized_inner = vec![];
+            let mut carry = vec![];
+            let mut switch = vec![];
 
             for (i, variant) in data.variants.iter().enumerate() {
                 let variant_name = &variant.ident;
-                let fields = parse_fields(get_fields(&variant.fields));
+                // serde numbers variants without the ones skipped while deserializing, so bincode
+                // would decode every variant after them as the wrong one.
+                if !utils::should_skip_serde_deserializing(&variant.attrs)
+                    && data
+                        .variants
+                        .iter()
+                        .take(i)
+                        .any(|previous| utils::should_skip_serde_deserializing(&previous.attrs))
+                {
+                    panic!("{variant_name} comes after a #[serde(skip)] variant; move skipped variants to the end of {root_name}!");
+                }
+                // serde can not round trip skipped variants, so we never generate them.
+                let is_skipped = utils::should_skip_serde(&variant.attrs);
+                let fields = if is_skipped {
+                    vec![]
+                } else {
+                    parse_fields(get_fields(&variant.fields))
+                };
                 let is_named = matches!(variant.fields, syn::Fields::Named(_));
+                if is_skipped {
+                    register_weight.push(quote! {
+                        v.register_weight(Self::__autarkie_id(), #i, 0);
+                    });
+                } else if let Some(weight) = get_variant_weight(variant) {
+                    register_weight.push(quote! {
+                        v.register_weight(Self::__autarkie_id(), #i, #weight);
+                    });
+                }
                 are_we_recursive.push(if !fields.is_empty() {
                     if is_named {
                         quote! {#root_name::#variant_name{..} => {
@@ -176,29 +234,127 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     }
                 } else {
                     quote! {
-                        #root_name::#variant_name {} => autarkie::visitor::NodeType::NonRecursive
+                        #root_name::#variant_name {..} => autarkie::visitor::NodeType::NonRecursive
                     }
                 });
 
-                let constructor =
-                    construct_generate_function_enum(&fields, is_named, root_name, variant_name);
+                let constructor = if is_skipped {
+                    quote! {None}
+                } else {
+                    let field_defs = get_field_defs(&fields);
+                    construct_generate_function_enum(
+                        &fields,
+                        field_defs,
+                        is_named,
+                        root_name,
+                        variant_name,
+                    )
+                };
                 generate.push(quote! {
                     #i => {
                         #constructor
                     }
                 });
 
+                // switching variants keeps the fields whose type the new variant also has.
+                let carried = fields.iter().map(|field| {
+                    let binding = &field.binding;
+                    let pattern = if field.is_carryable() {
+                        quote! {#binding}
+                    } else {
+                        quote! {_}
+                    };
+                    if is_named {
+                        let name = &field.name;
+                        quote! {#name: #pattern}
+                    } else {
+                        pattern
+                    }
+                });
+                let carry_fields = fields.iter().filter(|field| field.is_carryable()).map(|field| {
+                    let binding = &field.binding;
+                    let ty = &field.ty;
+                    quote! {
+                        autarkie_carried.push((<#ty>::__autarkie_id(), autarkie::serialize(#binding)));
+                    }
+                });
+                carry.push(if fields.is_empty() {
+                    quote! {#root_name::#variant_name{..} => #i}
+                } else if is_named {
+                    quote! {#root_name::#variant_name{#(#carried),*} => {#(#carry_fields)* #i}}
+                } else {
+                    quote! {#root_name::#variant_name(#(#carried),*) => {#(#carry_fields)* #i}}
+                });
+                let switched = if is_skipped {
+                    quote! {None}
+                } else {
+                    let field_defs = get_field_defs(&fields)
+                        .into_iter()
+                        .zip(&fields)
+                        .map(|(field_def, field)| {
+                            if !field.is_carryable() {
+                                return field_def;
+                            }
+                            let binding = &field.binding;
+                            let ty = &field.ty;
+                            let repair = field.settings().map(|settings| {
+                                quote! {
+                                    #binding.__autarkie_constrain(v, &#settings);
+                                }
+                            });
+                            quote! {
+                                let #binding: #ty = match autarkie_carried
+                                    .iter()
+                                    .position(|(id, _)| *id == <#ty>::__autarkie_id())
+                                {
+                                    Some(index) => {
+                                        let mut #binding: #ty = autarkie::deserialize(&mut autarkie_carried.remove(index).1.as_slice());
+                                        #repair
+                                        #binding
+                                    }
+                                    None => {
+                                        #field_def
+                                        #binding
+                                    }
+                                };
+                            }
+                        })
+                        .collect();
+                    construct_generate_function_enum(
+                        &fields,
+                        field_defs,
+                        is_named,
+                        root_name,
+                        variant_name,
+                    )
+                };
+                switch.push(quote! {
+                    #i => {
+                        #switched
+                    }
+                });
+
                 let field_fn = if !fields.is_empty() {
-                    let variant_fields_register = fields.iter().map(|field| {
-                        let name = &field.binding;
-                        let ty = &field.ty;
-                        let id = &field.id;
-                        quote! {
-                            v.register_field(((#id, #name.__autarkie_node_ty(v)), <#ty>::__autarkie_id()));
-                            #name.__autarkie_fields(v, #id);
-                            v.pop_field();
-                        }
-                    });
+                    let variant_fields_register = fields
+                        .iter()
+                        .filter(|field| !field.is_skipped())
+                        .map(|field| {
+                            let name = &field.binding;
+                            let ty = field.grammar_ty();
+                            let id = &field.id;
+                            let constraints = construct_constraint_checks(field, quote! {(*#name)});
+                            if !field.is_spliceable() {
+                                return constraints;
+                            }
+                            let (node_ty, inner) =
+                                construct_field_visit(field, quote! {(*#name)}, quote! {#id});
+                            quote! {
+                                v.register_field(((#id, #node_ty), <#ty>::__autarkie_id()));
+                                #constraints
+                                #inner
+                                v.pop_field();
+                            }
+                        });
                     let field_names = fields.iter().map(|field| field.binding_pair(is_named));
                     let match_arm = if is_named {
                         quote! {if let #root_name::#variant_name{#(#field_names),*} = self}
@@ -207,6 +363,7 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     };
                     Some(quote! {
                             #match_arm {
+                            v.visit_variant(Self::__autarkie_id(), #i);
                             v.register_field_stack(((#i, self.__autarkie_node_ty(v)), Self::__autarkie_id()));
                             #(#variant_fields_register)*
                             v.pop_field();
@@ -214,21 +371,25 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     })
                 } else {
                     Some(quote! {
-                        if let #root_name::#variant_name{} = self {}
+                        if let #root_name::#variant_name{..} = self {
+                            v.visit_variant(Self::__autarkie_id(), #i);
+                        }
                     })
                 };
 
                 fn_fields.push(field_fn);
-                if fields.is_empty() {
+                if fields.iter().all(|field| field.is_skipped()) {
                     register_ty.push(quote! {
                     // use something besides bool; bool is just a place holder.
                     v.register_ty(Some(Self::__autarkie_id_tuple()), <std::marker::PhantomData<bool>>::__autarkie_id_tuple(), #i);
                     v.pop_ty();
                 });
                 } else {
-                    let field_names = fields.iter().map(|field| {
-                        let ty = &field.ty;
+                    let field_names = fields.iter().filter(|field| !field.is_skipped()).map(|field| {
+                        let ty = field.grammar_ty();
+                        let constraints = construct_register_constraints(field);
                         quote! {
+                            #constraints
                             if !v.is_recursive(<#ty>::__autarkie_id()) {
                                 <#ty>::__autarkie_register(v, Some(Self::__autarkie_id_tuple()), #i);
                             } else {
@@ -241,13 +402,15 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 }
 
                 let fn_cmp = if !fields.is_empty() {
-                    let variant_fields_cmp = fields.iter().map(|field| {
+                    let variant_fields_cmp = fields.iter().filter(|field| field.is_spliceable()).map(|field| {
                         let name = &field.binding;
-                        let ty = &field.ty;
+                        let ty = field.grammar_ty();
                         let id = &field.id;
+                        let value = field.grammar_value(quote! {(*#name)});
+                        let cmps = construct_cmps(field, quote! {(*#name)}, quote! {#id});
                         quote! {
-                            v.register_field(((#id, #name.__autarkie_node_ty(v)), <#ty>::__autarkie_id()));
-                            #name.__autarkie_cmps(v, #id, __autarkie_val);
+                            v.register_field(((#id, #value.__autarkie_node_ty(v)), <#ty>::__autarkie_id()));
+                            #cmps
                             v.pop_field();
                         }
                     });
@@ -266,22 +429,20 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     })
                 } else {
                     Some(quote! {
-                        if let #root_name::#variant_name{} = self {}
+                        if let #root_name::#variant_name{..} = self {}
                     })
                 };
 
                 fn_cmps.push(fn_cmp);
                 let inner_mutate_variant = if !fields.is_empty() {
                     let field_names = fields.iter().map(|field| field.binding_pair(is_named));
-                    let variant_fields_mutate = fields.iter().map(|field| {
-                        let name = &field.binding;
-                        let id = &field.id;
-                        quote! {
-                            #id => {
-                                #name.__autarkie_mutate(autarkie_ty, autarkie_visitor, autarkie_path);
-                            },
-                        }
-                    });
+                    let variant_fields_mutate = fields
+                        .iter()
+                        .filter(|field| field.is_spliceable())
+                        .map(|field| {
+                            let name = &field.binding;
+                            construct_mutate_arm(field, quote! {(*#name)})
+                        });
 
                     let match_arm = if is_named {
                         quote! {if let #root_name::#variant_name{#(#field_names),*} = self }
@@ -314,15 +475,16 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 inner_mutate.push(inner_mutate_variant);
                 if !fields.is_empty() {
                     let field_names = fields.iter().map(|field| field.binding_pair(is_named));
-                    let serialized_fields = fields.iter().map(|field| {
+                    let serialized_fields = fields.iter().filter(|field| field.is_spliceable()).map(|field| {
                         let name = &field.binding;
-                        let ty = &field.ty;
+                        let ty = field.grammar_ty();
+                        let value = field.grammar_value(quote! {(*#name)});
                         quote! {
                             // todo: check fixed size
-                            if !matches!(#name.__autarkie_node_ty(autarkie_visitor), autarkie::visitor::NodeType::Iterable(..)) {
-                                autarkie_visitor.add_serialized(::autarkie::serialize(&#name), <#ty>::__autarkie_id());
+                            if !matches!(#value.__autarkie_node_ty(autarkie_visitor), autarkie::visitor::NodeType::Iterable(..)) {
+                                autarkie_visitor.add_serialized(::autarkie::serialize(&#value), <#ty>::__autarkie_id());
                             }
-                            #name.__autarkie_serialized(autarkie_visitor);
+                            #value.__autarkie_serialized(autarkie_visitor);
                         }
 
                     });
@@ -374,11 +536,12 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
                     fn __autarkie_register(v: &mut ::autarkie::Visitor, parent: Option<(::autarkie::tree::Id, String)>, variant: usize) {
                         v.register_ty(parent, Self::__autarkie_id_tuple(), variant);
+                        #(#register_weight)*
                         #(#register_ty)*;
                         v.pop_ty();
                     }
 
-                    fn __autarkie_cmps(&self, v: &mut ::autarkie::Visitor, __autarkie_index: usize, __autarkie_val: (u64, u64)) {
+                    fn __autarkie_cmps(&self, v: &mut ::autarkie::Visitor, __autarkie_index: usize, __autarkie_val: ::autarkie::CmpOperands) {
                         #(#fn_cmps)*;
                     }
 
@@ -401,6 +564,9 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         }
                         else {
                             match autarkie_ty {
+                                autarkie::MutationType::Copy(buffer) => {
+                                    **buffer = autarkie::serialize(self);
+                                }
                                 autarkie::MutationType::Splice(other) => {
                                     *self = autarkie::deserialize(other);
                                 }
@@ -411,10 +577,39 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                                         self.__autarkie_serialized(autarkie_visitor);
                                     }
                                 }
+                                autarkie::MutationType::SwitchVariant(autarkie_target, ref mut bias) => {
+                                    let mut autarkie_carried: Vec<(autarkie::Id, Vec<u8>)> = vec![];
+                                    let autarkie_current = match &*self {
+                                        #(#carry,)*
+                                    };
+                                    #[allow(unused_variables, unused_mut)]
+                                    let switched = (|| -> Option<Self> {
+                                        let v = &mut *autarkie_visitor;
+                                        let depth = bias;
+                                        let cur_depth = 0;
+                                        let (variant_id, is_recursive) = match autarkie_target {
+                                            Some(variant) => (*variant, v.is_recursive_variant(Self::__autarkie_id(), *variant)),
+                                            None => v.generate(&Self::__autarkie_id(), cur_depth)?,
+                                        };
+                                        if variant_id == autarkie_current {
+                                            return None;
+                                        }
+                                        match variant_id {
+                                            #(#switch,)*
+                                            _ => unreachable!("____Vb6tWm2E")
+                                        }
+                                    })();
+                                    if let Some(switched) = switched {
+                                        *self = switched;
+                                        autarkie_visitor.add_serialized(autarkie::serialize(&self), Self::__autarkie_id());
+                                        self.__autarkie_serialized(autarkie_visitor);
+                                    }
+                                }
                                 autarkie::MutationType::RecursiveReplace => {
                                     if self.__autarkie_node_ty(autarkie_visitor).is_recursive() {
-                                        // 0 depth == always non-recursive
-                                    if let Some(generated) = Self::__autarkie_generate(autarkie_visitor, &mut 0, 0, None) {
+                                        // at the generate depth, only non-recursive variants are picked
+                                    let autarkie_depth = autarkie_visitor.generate_depth();
+                                    if let Some(generated) = Self::__autarkie_generate(autarkie_visitor, &mut 0, autarkie_depth, None) {
                                         *self = generated;
                                         autarkie_visitor.add_serialized(autarkie::serialize(&self), Self::__autarkie_id());
                                         self.__autarkie_serialized(autarkie_visitor);
@@ -433,9 +628,114 @@ pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 #node_impl
             }
         }
-        Data::Union(..) => todo!(),
+        Data::Union(ref data) => {
+            syn::Error::new_spanned(data.union_token, "unions are not supported").to_compile_error()
+        }
+    };
+    TokenStream::from(quote! {
+        #expanded
+        #remote
+    })
+}
+
+/// Convert between a mirror and the foreign type set using #[autarkie(remote = "path::Type")].
+/// Like serde's remote derive, the mirror must have the same fields as the foreign type and they
+/// must be public. Otherwise, implement `From<Mirror> for Type` and `From<&Type> for Mirror` by
+/// hand.
+/// Fields of the foreign type use the mirror with #[autarkie(with = Mirror)].
+fn construct_remote(input: &DeriveInput) -> Option<proc_macro2::TokenStream> {
+    let mut remote = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("autarkie"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("remote") {
+                remote = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected #[autarkie(remote = \"path::Type\")]"))
+            }
+        })
+        .unwrap_or_else(|e| panic!("{e}"));
+    }
+    let remote = remote?;
+    // generic arguments are not allowed in patterns
+    let mut remote_pattern = remote.clone();
+    if let Some(last) = remote_pattern.segments.last_mut() {
+        last.arguments = PathArguments::None;
+    }
+    let root_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let members = |fields: &Fields| {
+        fields
+            .members()
+            .enumerate()
+            .map(|(id, member)| (member, format_ident!("__autarkie_field_{id}")))
+            .unzip::<_, _, Vec<_>, Vec<_>>()
+    };
+    let (into_remote, from_remote) = match &input.data {
+        Data::Struct(data) => {
+            let (members, _) = members(&data.fields);
+            (
+                quote! {Self {#(#members: mirror.#members),*}},
+                quote! {Self {#(#members: remote.#members.clone()),*}},
+            )
+        }
+        Data::Enum(data) => {
+            let (into_remote, from_remote) = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let variant_name = &variant.ident;
+                    let (members, bindings) = members(&variant.fields);
+                    (
+                        quote! {
+                            #root_name::#variant_name {#(#members: #bindings),*} => Self::#variant_name {#(#members: #bindings),*},
+                        },
+                        quote! {
+                            #remote_pattern::#variant_name {#(#members: #bindings),*} => Self::#variant_name {#(#members: #bindings.clone()),*},
+                        },
+                    )
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>();
+            (
+                quote! {
+                    match mirror {
+                        #(#into_remote)*
+                    }
+                },
+                quote! {
+                    #[allow(unreachable_patterns)]
+                    match remote {
+                        #(#from_remote)*
+                        // we only ever see variants converted from the mirror.
+                        _ => unreachable!("____Q7ZcW1tR"),
+                    }
+                },
+            )
+        }
+        Data::Union(data) => {
+            return Some(
+                syn::Error::new_spanned(data.union_token, "unions are not supported")
+                    .to_compile_error(),
+            )
+        }
     };
-    TokenStream::from(expanded)
+    Some(quote! {
+        impl #impl_generics From<#root_name #ty_generics> for #remote #where_clause {
+            fn from(mirror: #root_name #ty_generics) -> Self {
+                #into_remote
+            }
+        }
+
+        impl #impl_generics From<&#remote> for #root_name #ty_generics #where_clause {
+            fn from(remote: &#remote) -> Self {
+                #from_remote
+            }
+        }
+    })
 }
 
 fn parse_fields(
@@ -455,6 +755,12 @@ fn parse_fields(
                 None => Ident::new(&format!("_{id}"), field.span()),
             };
             let binding = Ident::new(&format!("__autarkie_field_{id}"), field.span());
+            if utils::is_serde_flatten(&field.attrs) {
+                panic!("#[serde(flatten)] on {name} is not supported; bincode cannot serialize flattened fields!");
+            }
+            if utils::is_serde_skip_serializing_if(&field.attrs) {
+                panic!("#[serde(skip_serializing_if)] on {name} is not supported; bincode cannot deserialize skipped fields!");
+            }
             GrammarField {
                 name,
                 binding,
@@ -475,6 +781,13 @@ fn get_field_defs(fields: &[GrammarField]) -> Vec<proc_macro2::TokenStream> {
             let name = &field.name;
             let binding = &field.binding;
             let ty = &field.ty;
+            // Skipped fields are never serialized, so we use the same value serde would.
+            if field.is_skipped() {
+                return match utils::get_serde_default(&field.attrs) {
+                    Some(default) => quote! {let #binding: #ty = #default();},
+                    None => quote! {let #binding: #ty = ::core::default::Default::default();},
+                };
+            }
             let mut generator = None;
             // The generator is a closure that is run immediately.
             // This allows us to sepcify literals for a field.
@@ -483,16 +796,7 @@ fn get_field_defs(fields: &[GrammarField]) -> Vec<proc_macro2::TokenStream> {
                 if let Meta::List(ref list) = attr.meta {
                     let ident = &list.path.segments.first().as_ref().unwrap().ident;
                     if  ident == "autarkie_literal" {
-                        let literals = list
-                            .tokens
-                            .clone()
-                            .into_iter()
-                            .filter(|i| {
-                                matches!(i, proc_macro2::TokenTree::Literal(_))
-                                    || matches!(i, proc_macro2::TokenTree::Group(_))
-                                    || matches!(i, proc_macro2::TokenTree::Ident(_))
-                            })
-                            .collect::<Vec<_>>();
+                        let literals = field.literals().unwrap_or_default();
                         let literals_len = literals.len() - 1;
                         // if we only have one literal
                         if literals_len == 0 {
@@ -510,41 +814,38 @@ fn get_field_defs(fields: &[GrammarField]) -> Vec<proc_macro2::TokenStream> {
                                 }();
                             });
                         }
-                    } else if ident == "autarkie_min_length" {
-                        let literals = list
-                            .tokens
-                            .clone()
-                            .into_iter()
-                            .filter(|i| {
-                                matches!(i, proc_macro2::TokenTree::Literal(_))
-                                    || matches!(i, proc_macro2::TokenTree::Group(_))
-                                    || matches!(i, proc_macro2::TokenTree::Ident(_))
-                            })
-                            .collect::<Vec<_>>();
-                        if literals.len() != 1 {
-                            panic!("autarkie_min_size(..) needs an unsigned integer literal value!");
-                        }
-                            let item = literals.first().unwrap();
+                    }
+                    else if ident == "autarkie_with" {
+                        let with: syn::Path = list.parse_args().unwrap_or_else(|_| {
+                            panic!("autarkie_with(..) needs a path to a function!")
+                        });
                             generator = Some(quote! {
-                                let #binding = <#ty>::__autarkie_generate(v, depth, if is_recursive {cur_depth + 1} else {cur_depth},
-                                Some(autarkie::GenerateSettings::Length(#item))
-                            )?;
+                                let #binding: #ty = #with(v, if is_recursive {cur_depth + 1} else {cur_depth})?;
                             });
                     }
-                    else if ident == "autarkie_range" {
-                        let range: syn::ExprRange = syn::parse(list.tokens.clone().into()).unwrap();
+                    else if ident == "autarkie_regex" {
+                        let pattern: LitStr = list.parse_args().unwrap_or_else(|_| {
+                            panic!("autarkie_regex(..) needs a string literal!")
+                        });
                             generator = Some(quote! {
-                                let #binding = <#ty>::__autarkie_generate(v, depth, if is_recursive {cur_depth + 1} else {cur_depth},
-                                Some(autarkie::GenerateSettings::Range(#range))
-                            )?;
+                                let #binding: #ty = v.generate_regex(#pattern).into();
                             });
-                }
+                    }
                 }
             }
             // If we do not have a literal attribute, we use the inner generate function of the type.
             if generator.is_none() {
-                generator = Some(quote! {
-                    let #binding = <#ty>::__autarkie_generate(v, depth, if is_recursive {cur_depth + 1} else {cur_depth}, None)?;
+                let settings = match field.settings() {
+                    Some(settings) => quote! {Some(#settings)},
+                    None => quote! {None},
+                };
+                generator = Some(match field.mirror() {
+                    Some(mirror) => quote! {
+                        let #binding: #ty = <#mirror>::__autarkie_generate(v, depth, if is_recursive {cur_depth + 1} else {cur_depth}, #settings)?.into();
+                    },
+                    None => quote! {
+                        let #binding = <#ty>::__autarkie_generate(v, depth, if is_recursive {cur_depth + 1} else {cur_depth}, #settings)?;
+                    },
                 });
             }
             // this should never happen, cause we either have a literal attribute or not.
@@ -554,6 +855,229 @@ fn get_field_defs(fields: &[GrammarField]) -> Vec<proc_macro2::TokenStream> {
         .collect::<Vec<_>>()
 }
 
+/// Parse the #[autarkie_weight(N)] attribute of an enum variant.
+fn get_variant_weight(variant: &syn::Variant) -> Option<usize> {
+    variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("autarkie_weight"))
+        .map(|attr| {
+            attr.parse_args::<LitInt>()
+                .and_then(|weight| weight.base10_parse::<usize>())
+                .unwrap_or_else(|_| {
+                    panic!("autarkie_weight(..) needs an unsigned integer literal value!")
+                })
+        })
+}
+
+/// Check the field's runtime constraints (see `Visitor::violates_constraints`).
+fn construct_constraint_checks(
+    field: &GrammarField,
+    accessor: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let regex = field.regex().map(|pattern| {
+        quote! {
+            v.visit_constraint(|v| v.matches_regex(#pattern, &#accessor));
+        }
+    });
+    let is_valid = construct_is_valid(field, accessor).map(|is_valid| {
+        quote! {
+            v.visit_constraint(|_| #is_valid);
+        }
+    });
+    quote! {
+        #regex
+        #is_valid
+    }
+}
+
+/// Check whether `value` satisfies the field's settings and literals, if it has any.
+fn construct_is_valid(
+    field: &GrammarField,
+    value: proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    let ty = &field.ty;
+    let settings = field
+        .settings()
+        .map(|settings| quote! {#value.__autarkie_satisfies(&#settings)});
+    let literals = field.literals().map(|literals| {
+        quote! {[#((#literals) as #ty),*].contains(&#value)}
+    });
+    match (settings, literals) {
+        (Some(settings), Some(literals)) => Some(quote! {#settings && #literals}),
+        (settings, literals) => settings.or(literals),
+    }
+}
+
+/// Pick one of the field's literals at random.
+fn construct_literal_pick(field: &GrammarField) -> Option<proc_macro2::TokenStream> {
+    let ty = &field.ty;
+    field.literals().map(|literals| {
+        let literals_len = literals.len() - 1;
+        quote! {
+            [#(#literals),*][autarkie_visitor.random_range(0, #literals_len)] as #ty
+        }
+    })
+}
+
+/// The field's NodeType and how to visit its inner fields.
+fn construct_field_visit(
+    field: &GrammarField,
+    value: proc_macro2::TokenStream,
+    index: proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let value = field.grammar_value(value);
+    (
+        quote! {#value.__autarkie_node_ty(v)},
+        quote! {#value.__autarkie_fields(v, #index);},
+    )
+}
+
+/// Register the field's constraints
+fn construct_register_constraints(field: &GrammarField) -> proc_macro2::TokenStream {
+    let regex = field
+        .regex()
+        .map(|pattern| quote! {v.register_regex(#pattern);});
+    let settings = (field.settings().is_some() || field.literals().is_some())
+        .then(|| quote! {v.register_constraint();});
+    quote! {
+        #regex
+        #settings
+    }
+}
+
+/// Find the field's cmps.
+/// If the field has a range, length or literals, we drop cmps which would break them.
+fn construct_cmps(
+    field: &GrammarField,
+    accessor: proc_macro2::TokenStream,
+    index: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let ty = field.grammar_ty();
+    let accessor = field.grammar_value(accessor);
+    match construct_is_valid(field, quote! {value}) {
+        Some(is_valid) => quote! {
+            let __autarkie_cmps_len = v.cmps_len();
+            #accessor.__autarkie_cmps(v, #index, __autarkie_val);
+            v.retain_cmps(__autarkie_cmps_len, |data| {
+                autarkie::maybe_deserialize::<#ty>(&mut &data[..]).is_some_and(|value| #is_valid)
+            });
+        },
+        None => quote! {
+            #accessor.__autarkie_cmps(v, #index, __autarkie_val);
+        },
+    }
+}
+
+/// Mutate a field by following the path.
+/// Fields with a custom generator (#[autarkie_with(..)]), a regex (#[autarkie_regex(..)]),
+/// literals (#[autarkie_literal(..)]) or settings (eg: #[autarkie_range(..)]) are replaced using
+/// them and only accept valid splices.
+/// Any other mutation (eg: appending to a #[autarkie_length(..)] Vec) is repaired afterwards.
+/// Fields with #[serde(with = "..")] are not part of the fields, they are only regenerated with their parent.
+/// Fields with #[autarkie(with = Mirror)] are mutated as their mirror.
+fn construct_mutate_arm(
+    field: &GrammarField,
+    accessor: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let id = &field.id;
+    let ty = &field.ty;
+    if let Some(mirror) = field.mirror() {
+        return quote! {
+            #id => {
+                let mut autarkie_mirror = <#mirror>::from(&#accessor);
+                autarkie_mirror.__autarkie_mutate(autarkie_ty, autarkie_visitor, autarkie_path);
+                #accessor = autarkie_mirror.into();
+            },
+        };
+    }
+    let settings = field.settings();
+    let generator = if let Some(pattern) = field.regex() {
+        Some((
+            quote! {_},
+            quote! {Some(autarkie_visitor.generate_regex(#pattern).into())},
+        ))
+    } else if let Some(with) = field.with_generator() {
+        // a smaller bias generates shallower, like the generate depth does for the other fields
+        Some((
+            quote! {ref mut autarkie_bias},
+            quote! {{
+                let autarkie_cur_depth = autarkie_visitor.generate_depth().saturating_sub(*autarkie_bias);
+                #with(autarkie_visitor, autarkie_cur_depth)
+            }},
+        ))
+    } else if let Some(pick) = construct_literal_pick(field) {
+        Some((quote! {_}, quote! {Some(#pick)}))
+    } else {
+        settings.as_ref().map(|settings| {
+            (
+                quote! {ref mut autarkie_bias},
+                quote! {<#ty>::__autarkie_generate(autarkie_visitor, autarkie_bias, 0, Some(#settings))},
+            )
+        })
+    };
+    let generate_arm = generator.map(|(bias, generate)| {
+        quote! {
+            ::autarkie::MutationType::GenerateReplace(#bias) if autarkie_path.is_empty() => {
+                if let Some(generated) = #generate {
+                    #accessor = generated;
+                    autarkie_visitor.add_serialized(::autarkie::serialize(&#accessor), <#ty>::__autarkie_id());
+                    #accessor.__autarkie_serialized(autarkie_visitor);
+                }
+            }
+        }
+    });
+    let splice_arm = if let Some(pattern) = field.regex() {
+        Some(quote! {autarkie_visitor.matches_regex(#pattern, &spliced)})
+    } else {
+        construct_is_valid(field, quote! {spliced})
+    }
+    .map(|is_valid| {
+        quote! {
+            autarkie::MutationType::Splice(other) if autarkie_path.is_empty() => {
+                // only splice valid values
+                let spliced: #ty = autarkie::deserialize(other);
+                if #is_valid {
+                    #accessor = spliced;
+                }
+            }
+        }
+    });
+    let repair = if let Some(pick) = construct_literal_pick(field) {
+        let is_valid = construct_is_valid(field, accessor.clone());
+        Some(quote! {
+            if !(#is_valid) {
+                #accessor = #pick;
+            }
+        })
+    } else {
+        settings.as_ref().map(|settings| {
+            quote! {
+                #accessor.__autarkie_constrain(autarkie_visitor, &#settings);
+            }
+        })
+    };
+    if generate_arm.is_none() && splice_arm.is_none() {
+        return quote! {
+            #id => {
+                #accessor.__autarkie_mutate(autarkie_ty, autarkie_visitor, autarkie_path);
+            },
+        };
+    }
+    quote! {
+        #id => {
+            match autarkie_ty {
+                #generate_arm
+                #splice_arm
+                _ => {
+                    #accessor.__autarkie_mutate(autarkie_ty, autarkie_visitor, autarkie_path);
+                    #repair
+                }
+            }
+        },
+    }
+}
+
 fn construct_generate_function_struct(
     fields: &[GrammarField],
     is_named: bool,
@@ -578,12 +1102,12 @@ fn construct_generate_function_struct(
 
 fn construct_generate_function_enum(
     fields: &[GrammarField],
+    field_defs: Vec<proc_macro2::TokenStream>,
     is_named: bool,
     root_name: &Ident,
     variant_name: &Ident,
 ) -> proc_macro2::TokenStream {
     if !fields.is_empty() {
-        let field_defs = get_field_defs(fields);
         let fields = fields.iter().map(|field| field.binding_pair(is_named));
         // if the enum variant is
         // non named -> Enum::Variant(x, y, z)
@@ -629,6 +1153,170 @@ impl GrammarField {
         }
     }
 
+    /// The field's settings from #[autarkie_length(..)], #[autarkie_range(..)] and
+    /// #[autarkie_distribution(..)], if any.
+    /// Float ranges are always inclusive.
+    fn settings(&self) -> Option<proc_macro2::TokenStream> {
+        if let Some(attr) = self
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("autarkie_length"))
+        {
+            let length = attr.parse_args::<LitInt>().unwrap_or_else(|_| {
+                panic!("autarkie_length(..) needs an unsigned integer literal value!")
+            });
+            return Some(quote! {autarkie::GenerateSettings::Length(#length)});
+        }
+        let range = self
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("autarkie_range"))
+            .map(|attr| {
+                attr.parse_args::<ExprRange>()
+                    .unwrap_or_else(|_| panic!("autarkie_range(..) needs a range!"))
+            });
+        let distribution = self
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("autarkie_distribution"))
+            .map(|attr| {
+                let distribution = attr.parse_args::<Ident>().map(|i| i.to_string());
+                match distribution.as_deref() {
+                    Ok("uniform") => quote! {autarkie::Distribution::Uniform},
+                    Ok("boundary") => quote! {autarkie::Distribution::Boundary},
+                    Ok("small") => quote! {autarkie::Distribution::Small},
+                    Ok("log_uniform") => quote! {autarkie::Distribution::LogUniform},
+                    _ => panic!(
+                        "autarkie_distribution(..) must be one of uniform, boundary, small or log_uniform!"
+                    ),
+                }
+            });
+        let Some(range) = range else {
+            return distribution.map(|distribution| {
+                quote! {autarkie::GenerateSettings::Distribution(#distribution)}
+            });
+        };
+        let distribution = distribution.unwrap_or(quote! {autarkie::Distribution::Uniform});
+        let is_float = matches!(&self.ty, Type::Path(ty) if ty.path.is_ident("f32") || ty.path.is_ident("f64"));
+        if is_float {
+            let start = range
+                .start
+                .map(|start| quote! {(#start) as f64})
+                .unwrap_or(quote! {f64::MIN});
+            let end = range
+                .end
+                .map(|end| quote! {(#end) as f64})
+                .unwrap_or(quote! {f64::MAX});
+            Some(quote! {autarkie::GenerateSettings::FloatRange(#start..=#end, #distribution)})
+        } else {
+            let start = range
+                .start
+                .map(|start| quote! {(#start) as i128})
+                .unwrap_or(quote! {i128::MIN});
+            let end = match (range.end, range.limits) {
+                (Some(end), RangeLimits::HalfOpen(_)) => quote! {(#end) as i128 - 1},
+                (Some(end), RangeLimits::Closed(_)) => quote! {(#end) as i128},
+                (None, _) => quote! {i128::MAX},
+            };
+            Some(quote! {autarkie::GenerateSettings::Range(#start..=#end, #distribution)})
+        }
+    }
+
+    /// Whether serde skips the field, eg: #[serde(skip)].
+    /// Skipped fields are not part of the grammar.
+    fn is_skipped(&self) -> bool {
+        utils::should_skip_serde(&self.attrs)
+    }
+
+    /// Whether the field is (de)serialized as Self, so we can descend into it, splice it and
+    /// collect its chunks.
+    /// Fields with #[serde(with = "..")] have a custom wire format, so they are left out of the
+    /// fields and only regenerated with their parent. Unless they have a mirror, which stands in for the field everywhere.
+    fn is_spliceable(&self) -> bool {
+        !self.is_skipped() && (self.mirror().is_some() || !utils::has_serde_with(&self.attrs))
+    }
+
+    /// Whether switching variants may carry the field over to a field of the same type.
+    /// Fields with a mirror or a custom generator are always generated anew.
+    fn is_carryable(&self) -> bool {
+        self.is_spliceable()
+            && self.mirror().is_none()
+            && self.literals().is_none()
+            && self.regex().is_none()
+            && self.with_generator().is_none()
+    }
+
+    /// The local type mirroring the field's foreign type, set using #[autarkie(with = Mirror)].
+    fn mirror(&self) -> Option<Type> {
+        let mut mirror = None;
+        for attr in self
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("autarkie"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("with") {
+                    mirror = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected #[autarkie(with = Mirror)]"))
+                }
+            })
+            .unwrap_or_else(|e| panic!("{e}"));
+        }
+        mirror
+    }
+
+    /// The type implementing Node for the field: either its mirror or the field's type.
+    fn grammar_ty(&self) -> Type {
+        self.mirror().unwrap_or_else(|| self.ty.clone())
+    }
+
+    /// The field as its grammar type.
+    fn grammar_value(&self, accessor: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match self.mirror() {
+            Some(mirror) => quote! {<#mirror>::from(&#accessor)},
+            None => accessor,
+        }
+    }
+
+    /// The literals of #[autarkie_literal(..)], if any.
+    fn literals(&self) -> Option<Vec<proc_macro2::TokenTree>> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("autarkie_literal"))
+            .and_then(|attr| match &attr.meta {
+                Meta::List(list) => Some(
+                    list.tokens
+                        .clone()
+                        .into_iter()
+                        .filter(|i| {
+                            matches!(i, proc_macro2::TokenTree::Literal(_))
+                                || matches!(i, proc_macro2::TokenTree::Group(_))
+                                || matches!(i, proc_macro2::TokenTree::Ident(_))
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            })
+    }
+
+    /// The regex the field must match, if any.
+    fn regex(&self) -> Option<LitStr> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("autarkie_regex"))
+            .and_then(|attr| attr.parse_args().ok())
+    }
+
+    /// The user provided generator function of the field, if any.
+    fn with_generator(&self) -> Option<syn::Path> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("autarkie_with"))
+            .and_then(|attr| attr.parse_args().ok())
+    }
+
     fn binding_pair(&self, is_named: bool) -> proc_macro2::TokenStream {
         let binding = &self.binding;
         if is_named {
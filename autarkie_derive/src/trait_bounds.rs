@@ -145,6 +145,7 @@ pub fn get_types_to_add_trait_bound(
             !utils::is_compact(f)
                 && utils::get_encoded_as_type(f).is_none()
                 && !utils::should_skip(&f.attrs)
+                && !utils::should_skip_serde(&f.attrs)
         };
         let res = collect_types(data, needs_codec_bound)?
             .into_iter()
@@ -190,7 +191,9 @@ fn collect_types(data: &syn::Data, type_filter: fn(&syn::Field) -> bool) -> Resu
         Data::Enum(ref data) => data
             .variants
             .iter()
-            .filter(|variant| !utils::should_skip(&variant.attrs))
+            .filter(|variant| {
+                !utils::should_skip(&variant.attrs) && !utils::should_skip_serde(&variant.attrs)
+            })
             .flat_map(|variant| match &variant.fields {
                 Fields::Named(FieldsNamed { named: fields, .. })
                 | Fields::Unnamed(FieldsUnnamed {
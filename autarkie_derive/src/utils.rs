@@ -23,7 +23,7 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
     parse::Parse, parse_quote, punctuated::Punctuated, spanned::Spanned, token, Attribute, Expr,
-    ExprLit, Field, Lit, Meta, Path, Token,
+    ExprLit, Field, Lit, Meta, MetaNameValue, Path, Token,
 };
 
 fn find_meta_item<'a, F, R, I, M>(mut itr: I, mut pred: F) -> Option<R>
@@ -97,6 +97,78 @@ pub fn should_skip(attrs: &[Attribute]) -> bool {
     .is_some()
 }
 
+/// The items of all `#[serde(..)]` attributes, eg: `skip` or `with = "module"`.
+fn serde_items(attrs: &[Attribute]) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .flat_map(|attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Look for a `#[serde(skip)]`, `#[serde(skip_serializing)]` or `#[serde(skip_deserializing)]` in
+/// the given attributes.
+pub fn should_skip_serde(attrs: &[Attribute]) -> bool {
+    serde_items(attrs).iter().any(|meta| {
+        let path = meta.path();
+        path.is_ident("skip")
+            || path.is_ident("skip_serializing")
+            || path.is_ident("skip_deserializing")
+    })
+}
+
+/// Look for a `#[serde(skip)]` or `#[serde(skip_deserializing)]` in the given attributes.
+pub fn should_skip_serde_deserializing(attrs: &[Attribute]) -> bool {
+    serde_items(attrs).iter().any(|meta| {
+        let path = meta.path();
+        path.is_ident("skip") || path.is_ident("skip_deserializing")
+    })
+}
+
+/// Look for a `#[serde(default = "path")]` in the given attributes.
+pub fn get_serde_default(attrs: &[Attribute]) -> Option<Path> {
+    serde_items(attrs).into_iter().find_map(|meta| match meta {
+        Meta::NameValue(MetaNameValue {
+            path,
+            value:
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(default),
+                    ..
+                }),
+            ..
+        }) if path.is_ident("default") => default.parse().ok(),
+        _ => None,
+    })
+}
+
+/// Look for a `#[serde(with = "..")]`, `#[serde(serialize_with = "..")]` or
+/// `#[serde(deserialize_with = "..")]` in the given attributes.
+pub fn has_serde_with(attrs: &[Attribute]) -> bool {
+    serde_items(attrs).iter().any(|meta| {
+        let path = meta.path();
+        path.is_ident("with")
+            || path.is_ident("serialize_with")
+            || path.is_ident("deserialize_with")
+    })
+}
+
+/// Look for a `#[serde(flatten)]` in the given attributes.
+pub fn is_serde_flatten(attrs: &[Attribute]) -> bool {
+    serde_items(attrs)
+        .iter()
+        .any(|meta| meta.path().is_ident("flatten"))
+}
+
+/// Look for a `#[serde(skip_serializing_if = "..")]` in the given attributes.
+pub fn is_serde_skip_serializing_if(attrs: &[Attribute]) -> bool {
+    serde_items(attrs)
+        .iter()
+        .any(|meta| meta.path().is_ident("skip_serializing_if"))
+}
+
 /// This struct matches `crate = ...` where the ellipsis is a `Path`.
 struct CratePath {
     _crate_token: Token![crate],
@@ -77,14 +77,270 @@ pub enum ShadowedMacroLocalEnum {
     Tuple(u32, u32),
 }
 
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize, PartialEq)]
+pub enum Weighted {
+    #[autarkie_weight(0)]
+    Never,
+    #[autarkie_weight(5)]
+    Often(u8),
+    Sometimes,
+}
+
+fn register_number(v: &mut autarkie::Visitor, _cur_depth: usize) -> Option<u8> {
+    Some(v.random_range(0, 15) as u8)
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Instruction {
+    #[autarkie_with(register_number)]
+    dst: u8,
+    op: Operand,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub enum Operand {
+    Register(#[autarkie_with(register_number)] u8),
+    Immediate(u32),
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Lexical {
+    #[autarkie_regex("[a-z_][a-z0-9_]{0,7}")]
+    identifier: String,
+    #[autarkie_regex("0x[0-9a-f]+")]
+    hex: String,
+    /// Only the non-word characters match, because of the word boundary
+    #[autarkie_regex(r"[a\-.,;:]\bc")]
+    bounded: String,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Ranged {
+    #[autarkie_range(-5..=5)]
+    small: i8,
+    #[autarkie_range(10..20)]
+    huge: u128,
+    #[autarkie_range(0.5..=1.5)]
+    float: f64,
+    #[autarkie_distribution(boundary)]
+    boundary: u32,
+    #[autarkie_range(100..=200)]
+    #[autarkie_distribution(log_uniform)]
+    log: i64,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Constrained {
+    #[autarkie_length(4)]
+    fixed: Vec<u8>,
+    #[autarkie_range(1..=3)]
+    bounded: Vec<u16>,
+    #[autarkie_literal(7, 9)]
+    opcode: u8,
+    #[autarkie_range(2..)]
+    open: Vec<u8>,
+}
+
+fn default_name() -> String {
+    "default".to_string()
+}
+
+mod as_string {
+    pub fn serialize<S: serde::Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+#[allow(dead_code)]
+pub struct SerdeAttributes {
+    #[serde(skip)]
+    cache: std::cell::Cell<u64>,
+    #[serde(skip, default = "default_name")]
+    name: String,
+    #[serde(with = "as_string")]
+    number: u32,
+    #[serde(default)]
+    value: u8,
+    variant: SerdeVariant,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub enum SerdeVariant {
+    Public(u8),
+    #[serde(skip)]
+    Internal(std::cell::Cell<u64>),
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+#[autarkie(remote = "std::ops::Range<u8>")]
+pub struct RangeDef {
+    start: u8,
+    end: u8,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Ipv4AddrDef([u8; 4]);
+
+impl From<Ipv4AddrDef> for std::net::Ipv4Addr {
+    fn from(mirror: Ipv4AddrDef) -> Self {
+        Self::from(mirror.0)
+    }
+}
+
+impl From<&std::net::Ipv4Addr> for Ipv4AddrDef {
+    fn from(remote: &std::net::Ipv4Addr) -> Self {
+        Self(remote.octets())
+    }
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+#[autarkie(remote = "std::ops::Bound<u8>")]
+pub enum BoundDef {
+    Included(u8),
+    Excluded(u8),
+    Unbounded,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Foreign {
+    #[autarkie(with = RangeDef)]
+    range: std::ops::Range<u8>,
+    #[autarkie(with = Ipv4AddrDef)]
+    addr: std::net::Ipv4Addr,
+    #[autarkie(with = BoundDef)]
+    bound: std::ops::Bound<u8>,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Collections {
+    map: std::collections::BTreeMap<u8, u16>,
+    hash_map: std::collections::HashMap<u8, u8>,
+    set: std::collections::BTreeSet<u8>,
+    hash_set: std::collections::HashSet<u8>,
+    deque: std::collections::VecDeque<u8>,
+    heap: std::collections::BinaryHeap<u8>,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct StdTypes {
+    shared: std::rc::Rc<u8>,
+    atomic: std::sync::Arc<Vec<u8>>,
+    non_zero: std::num::NonZeroU8,
+    #[autarkie_range(-1..=1)]
+    signed_non_zero: std::num::NonZeroI8,
+    wrapping: std::num::Wrapping<u32>,
+    duration: std::time::Duration,
+    ip: std::net::IpAddr,
+    socket: std::net::SocketAddr,
+    path: std::path::PathBuf,
+    os_string: std::ffi::OsString,
+    range: std::ops::Range<u16>,
+    cell: std::cell::Cell<u8>,
+    ref_cell: std::cell::RefCell<String>,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tree {
+    Leaf(u8),
+    // recursive
+    Node(Box<Tree>),
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize, PartialEq)]
+pub enum Arith {
+    Lit(u8),
+    Add(Box<Arith>, Box<Arith>),
+    Sub(Box<Arith>, Box<Arith>),
+    Neg { operand: Box<Arith> },
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize, PartialEq)]
+pub struct Optional {
+    value: Option<u8>,
+    arith: Arith,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize, PartialEq)]
+pub struct Forest {
+    left: Tree,
+    right: Tree,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Text {
+    text: String,
+    bytes: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize, PartialEq)]
+pub struct Packet {
+    method: String,
+    payload: Vec<u8>,
+    magic: [u8; 4],
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct Encoded {
+    swapped: u32,
+    narrow: u8,
+    signed: i8,
+    shifted: u16,
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize, PartialEq)]
+pub struct Query {
+    table: String,
+    id: u32,
+    limit: Option<u16>,
+}
+
+fn render_query(query: &Query, renderer: &mut autarkie::Renderer) {
+    renderer.write("SELECT * FROM ");
+    renderer.child(0, |r| r.write(&query.table));
+    renderer.write(" WHERE id = ");
+    renderer.child(1, |r| r.write(query.id.to_string()));
+    if let Some(limit) = query.limit {
+        renderer.write(" LIMIT ");
+        renderer.child(2, |r| r.child(0, |r| r.write(limit.to_string())));
+    }
+}
+
+#[derive(Clone, Debug, Grammar, Serialize, Deserialize)]
+pub struct ZeroCopy {
+    bytes: std::borrow::Cow<'static, [u8]>,
+    pair: std::borrow::Cow<'static, (u8, u16)>,
+    trees: std::borrow::Cow<'static, [Tree]>,
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::{BTreeMap, BTreeSet};
+    use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
     use autarkie::Visitor;
 
     use super::*;
 
+    /// A visitor with `T` registered, using the depths most tests need
+    fn visitor_for<T: Node>() -> Visitor {
+        let mut visitor = Visitor::new(
+            0,
+            autarkie::DepthInfo {
+                generate: 2,
+                iterate: 2,
+            },
+            0,
+        );
+        T::__autarkie_register(&mut visitor, None, 0);
+        visitor.calculate_recursion();
+        visitor
+    }
+
     #[test]
     fn derive_allows_fields_named_like_macro_locals() {
         let _ = ShadowedMacroLocals {
@@ -117,19 +373,20 @@ mod tests {
                 generate: 2,
                 iterate: 2,
             },
+            0,
         );
         Statement::__autarkie_register(&mut visitor, None, 0);
+        let recursion = visitor.calculate_recursion();
         assert_eq!(
-            visitor.calculate_recursion(),
+            recursion
+                .into_iter()
+                .map(|(id, variants)| (visitor.ty_name_map()[&id].clone(), variants))
+                .collect::<BTreeMap<_, _>>(),
             BTreeMap::from_iter([
                 (
                     "autarkie_test::Expr".to_string(),
                     BTreeSet::from_iter([2, 3, 4, 5, 6, 7, 8, 9])
                 ),
-                (
-                    "core::option::Option<autarkie_test::Expr>".to_string(),
-                    BTreeSet::from_iter([1])
-                ),
                 (
                     "core::result::Result<autarkie_test::InnerBoxed, usize>".to_string(),
                     BTreeSet::from_iter([0])
@@ -137,4 +394,1022 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn generate_respects_variant_weights() {
+        let mut visitor = visitor_for::<Weighted>();
+        let mut often = 0;
+        for _ in 0..1000 {
+            let generated =
+                Weighted::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+            assert_ne!(generated, Weighted::Never);
+            if matches!(generated, Weighted::Often(_)) {
+                often += 1;
+            }
+        }
+        assert!(often > 700);
+    }
+
+    #[test]
+    fn tuning_disables_variants() {
+        let mut visitor = visitor_for::<Weighted>();
+        let tuning = BTreeMap::from_iter([(
+            Weighted::__autarkie_id_name(),
+            autarkie::TypeTuning {
+                disable: BTreeSet::from_iter([1]),
+                weights: BTreeMap::from_iter([(0, 1)]),
+            },
+        )]);
+        visitor.apply_tuning(tuning).unwrap();
+        for _ in 0..100 {
+            let generated =
+                Weighted::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+            assert!(!visitor.violates_constraints(&generated));
+            assert!(!matches!(generated, Weighted::Often(_)));
+        }
+        assert!(visitor.violates_constraints(&Weighted::Often(0)));
+        assert!(!visitor.violates_constraints(&Weighted::Sometimes));
+    }
+
+    #[test]
+    fn custom_generators_are_used_for_generation_and_replacement() {
+        let mut visitor = visitor_for::<Instruction>();
+        for _ in 0..100 {
+            let mut generated = Instruction::__autarkie_generate(&mut visitor, &mut 0, 0, None)
+                .expect("generation");
+            assert!(generated.dst < 16);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::GenerateReplace(0),
+                &mut visitor,
+                VecDeque::from_iter([0]),
+            );
+            assert!(generated.dst < 16);
+            if let Operand::Register(_) = generated.op {
+                generated.__autarkie_mutate(
+                    &mut autarkie::MutationType::GenerateReplace(0),
+                    &mut visitor,
+                    VecDeque::from_iter([1, 0, 0]),
+                );
+            }
+            if let Operand::Register(register) = generated.op {
+                assert!(register < 16);
+            }
+        }
+    }
+
+    #[test]
+    fn regex_fields_are_generated_and_enforced() {
+        let mut visitor = visitor_for::<Lexical>();
+        for _ in 0..100 {
+            let mut generated =
+                Lexical::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+            assert!(visitor.matches_regex("[a-z_][a-z0-9_]{0,7}", &generated.identifier));
+            assert!(visitor.matches_regex("0x[0-9a-f]+", &generated.hex));
+            assert_ne!(generated.bounded, "ac");
+            assert!(!visitor.violates_constraints(&generated));
+            let invalid = autarkie::serialize(&"not hex".to_string());
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut invalid.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([1]),
+            );
+            assert!(visitor.matches_regex("0x[0-9a-f]+", &generated.hex));
+        }
+        let invalid = Lexical {
+            identifier: "0abc".to_string(),
+            hex: "0x1".to_string(),
+            bounded: "-c".to_string(),
+        };
+        assert!(visitor.violates_constraints(&invalid));
+    }
+
+    #[test]
+    fn ranges_hold_across_generation_and_mutation() {
+        let mut visitor = visitor_for::<Ranged>();
+        for _ in 0..200 {
+            let mut generated =
+                Ranged::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+            assert!((-5..=5).contains(&generated.small));
+            assert!((10..20).contains(&generated.huge));
+            assert!((0.5..=1.5).contains(&generated.float));
+            assert!([0, 1, u32::MAX - 1, u32::MAX].contains(&generated.boundary));
+            assert!((100..=200).contains(&generated.log));
+            assert!(!visitor.violates_constraints(&generated));
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::GenerateReplace(0),
+                &mut visitor,
+                VecDeque::from_iter([0]),
+            );
+            assert!((-5..=5).contains(&generated.small));
+            let out_of_range = autarkie::serialize(&100_i8);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut out_of_range.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([0]),
+            );
+            assert!((-5..=5).contains(&generated.small));
+            generated.__autarkie_cmps(
+                &mut visitor,
+                0,
+                autarkie::CmpOperands::Int(generated.small as u64, 100),
+            );
+            assert!(visitor.cmps().iter().all(|(path, _)| path[0].0 .0 != 0));
+        }
+        let mut invalid = Ranged::__autarkie_generate(&mut visitor, &mut 0, 0, None).unwrap();
+        invalid.huge = 20;
+        assert!(visitor.violates_constraints(&invalid));
+    }
+
+    #[test]
+    fn constraints_hold_across_mutations() {
+        let mut visitor = visitor_for::<Constrained>();
+        let appended = [autarkie::serialize(&5_u8), autarkie::serialize(&5_u16)];
+        for _ in 0..100 {
+            let mut generated = Constrained::__autarkie_generate(&mut visitor, &mut 0, 0, None)
+                .expect("generation");
+            assert!(!visitor.violates_constraints(&generated));
+            for (field, appended) in appended.iter().enumerate() {
+                generated.__autarkie_mutate(
+                    &mut autarkie::MutationType::GenerateAppend(0),
+                    &mut visitor,
+                    VecDeque::from_iter([field]),
+                );
+                generated.__autarkie_mutate(
+                    &mut autarkie::MutationType::SpliceAppend(&mut appended.as_slice()),
+                    &mut visitor,
+                    VecDeque::from_iter([field]),
+                );
+                assert!(!visitor.violates_constraints(&generated));
+                for _ in 0..4 {
+                    generated.__autarkie_mutate(
+                        &mut autarkie::MutationType::IterablePop(0),
+                        &mut visitor,
+                        VecDeque::from_iter([field]),
+                    );
+                }
+                assert!(!visitor.violates_constraints(&generated));
+            }
+            assert_eq!(generated.fixed.len(), 4);
+            assert!((1..=3).contains(&generated.bounded.len()));
+            // open ranges are generated like unbounded iterables, from their minimum
+            assert!((2..=4).contains(&generated.open.len()));
+            let invalid = autarkie::serialize(&8_u8);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut invalid.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([2]),
+            );
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::GenerateReplace(0),
+                &mut visitor,
+                VecDeque::from_iter([2]),
+            );
+            assert!([7, 9].contains(&generated.opcode));
+            generated.__autarkie_cmps(
+                &mut visitor,
+                0,
+                autarkie::CmpOperands::Int(generated.opcode as u64, 8),
+            );
+            // 8 itself is not a literal, only the off-by-one replacements are
+            assert!(visitor
+                .cmps()
+                .iter()
+                .filter(|(path, _)| path[0].0 .0 == 2)
+                .all(|(_, data)| [7, 9].contains(&autarkie::deserialize::<u8>(&mut &data[..]))));
+        }
+    }
+
+    #[test]
+    fn serde_attributes_are_honored() {
+        let mut visitor = visitor_for::<SerdeAttributes>();
+        for _ in 0..100 {
+            let generated = SerdeAttributes::__autarkie_generate(&mut visitor, &mut 0, 0, None)
+                .expect("generation");
+            assert_eq!(generated.cache.get(), 0);
+            assert_eq!(generated.name, "default");
+            assert!(matches!(generated.variant, SerdeVariant::Public(_)));
+            // skipped fields are not part of the grammar and #[serde(with)] fields are only
+            // regenerated with their parent, so mutators can not pick them
+            generated.__autarkie_fields(&mut visitor, 0);
+            let fields = visitor.fields();
+            assert!(!fields.is_empty());
+            assert!(fields.iter().all(|path| path[0].0 .0 >= 3));
+            let serialized = autarkie::serialize(&generated);
+            let deserialized: SerdeAttributes = autarkie::deserialize(&mut serialized.as_slice());
+            assert_eq!(deserialized.number, generated.number);
+        }
+    }
+
+    #[test]
+    fn foreign_fields_use_their_mirror() {
+        let mut visitor = visitor_for::<Foreign>();
+        for _ in 0..100 {
+            let mut generated =
+                Foreign::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+            generated.__autarkie_fields(&mut visitor, 0);
+            let fields = visitor.fields();
+            // we descend into the mirror
+            assert!(fields
+                .iter()
+                .any(|path| path.len() == 2 && path[0].0 .0 == 0 && path[1].0 .0 == 1));
+            let end = autarkie::serialize(&generated.range.end.wrapping_add(1));
+            let expected = generated.range.end.wrapping_add(1);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut end.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([0, 1]),
+            );
+            assert_eq!(generated.range.end, expected);
+            let addr = autarkie::serialize(&[127_u8, 0, 0, 1]);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut addr.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([1, 0]),
+            );
+            assert_eq!(generated.addr, std::net::Ipv4Addr::LOCALHOST);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::GenerateReplace(0),
+                &mut visitor,
+                VecDeque::from_iter([2]),
+            );
+            generated.__autarkie_serialized(&mut visitor);
+            assert!(visitor
+                .serialized()
+                .iter()
+                .any(|(_, id)| *id == RangeDef::__autarkie_id()));
+        }
+    }
+    #[test]
+    fn collections_are_generated_and_mutated() {
+        let mut visitor = Visitor::new(
+            0,
+            autarkie::DepthInfo {
+                generate: 2,
+                iterate: 4,
+            },
+            0,
+        );
+        Collections::__autarkie_register(&mut visitor, None, 0);
+        visitor.calculate_recursion();
+        let mut non_empty = 0;
+        for _ in 0..100 {
+            let mut generated = Collections::__autarkie_generate(&mut visitor, &mut 0, 0, None)
+                .expect("generation");
+            if !generated.map.is_empty() && !generated.heap.is_empty() {
+                non_empty += 1;
+            }
+            for field in 0..6 {
+                generated.__autarkie_mutate(
+                    &mut autarkie::MutationType::GenerateAppend(0),
+                    &mut visitor,
+                    VecDeque::from_iter([field]),
+                );
+            }
+            assert!(!generated.map.is_empty());
+            assert!(!generated.set.is_empty());
+            assert!(!generated.deque.is_empty());
+            assert!(!generated.heap.is_empty());
+            // a key collision keeps both entries
+            let key = *generated.map.keys().next().unwrap();
+            let colliding = key.wrapping_add(1);
+            generated.map.entry(colliding).or_insert(0);
+            let len = generated.map.len();
+            let spliced = autarkie::serialize(&colliding);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut spliced.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([0, 0, 0]),
+            );
+            assert_eq!(generated.map.len(), len);
+            assert!(generated.map.contains_key(&key));
+            let appended = autarkie::serialize(&(colliding.wrapping_add(1), 7_u16));
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::SpliceAppend(&mut appended.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([0]),
+            );
+            assert!(generated.map.contains_key(&colliding.wrapping_add(1)));
+            let len = generated.set.len();
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::IterablePop(0),
+                &mut visitor,
+                VecDeque::from_iter([2]),
+            );
+            assert_eq!(generated.set.len(), len - 1);
+            let len = generated.heap.len();
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::IterablePop(0),
+                &mut visitor,
+                VecDeque::from_iter([5]),
+            );
+            assert_eq!(generated.heap.len(), len - 1);
+            // we descend into the entries of a map
+            let first = *generated.map.keys().next().unwrap();
+            let value = autarkie::serialize(&9_u16);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut value.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([0, 0, 1]),
+            );
+            assert_eq!(generated.map[&first], 9);
+            generated.__autarkie_serialized(&mut visitor);
+            assert!(visitor
+                .serialized()
+                .iter()
+                .any(|(_, id)| *id == <(u8, u16)>::__autarkie_id()));
+        }
+        assert!(non_empty > 0);
+    }
+    #[test]
+    fn std_types_are_generated_and_mutated() {
+        let mut visitor = visitor_for::<StdTypes>();
+        visitor.register_string("/tmp".to_string());
+        for _ in 0..200 {
+            let mut generated =
+                StdTypes::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+            assert!([-1, 1].contains(&generated.signed_non_zero.get()));
+            let serialized = autarkie::serialize(&generated);
+            let deserialized: StdTypes = autarkie::deserialize(&mut serialized.as_slice());
+            assert_eq!(deserialized.socket, generated.socket);
+            assert_eq!(deserialized.duration, generated.duration);
+            for field in 0..13 {
+                generated.__autarkie_mutate(
+                    &mut autarkie::MutationType::GenerateReplace(0),
+                    &mut visitor,
+                    VecDeque::from_iter([field]),
+                );
+            }
+            assert!([-1, 1].contains(&generated.signed_non_zero.get()));
+            // shared values are cloned before they are mutated
+            let shared = generated.shared.clone();
+            let spliced = autarkie::serialize(&shared.wrapping_add(1));
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut spliced.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([0]),
+            );
+            assert_eq!(*generated.shared, shared.wrapping_add(1));
+            assert_ne!(*generated.shared, *shared);
+            let end = autarkie::serialize(&7_u16);
+            generated.__autarkie_mutate(
+                &mut autarkie::MutationType::Splice(&mut end.as_slice()),
+                &mut visitor,
+                VecDeque::from_iter([10, 1]),
+            );
+            assert_eq!(generated.range.end, 7);
+            generated.__autarkie_cmps(
+                &mut visitor,
+                0,
+                autarkie::CmpOperands::Int(generated.non_zero.get() as u64, 0),
+            );
+            assert!(visitor.cmps().iter().all(|(path, _)| path[0].0 .0 != 2));
+        }
+    }
+    #[test]
+    fn non_zero_cmps_are_checked_after_truncation() {
+        let mut visitor = visitor_for::<std::num::NonZeroU8>();
+        let non_zero = std::num::NonZeroU8::new(5).unwrap();
+        // 256 truncates to zero
+        non_zero.__autarkie_cmps(&mut visitor, 0, autarkie::CmpOperands::Int(5, 256));
+        let cmps = visitor.cmps();
+        assert!(!cmps.is_empty());
+        assert!(cmps
+            .iter()
+            .all(|(_, data)| autarkie::deserialize::<u8>(&mut data.as_slice()) != 0));
+    }
+    #[test]
+    fn non_zero_cmps_drop_transformed_zeros() {
+        let mut visitor = visitor_for::<std::num::NonZeroU8>();
+        let one = std::num::NonZeroU8::new(1).unwrap();
+        // 1 - 1 when matching off by one
+        one.__autarkie_cmps(&mut visitor, 0, autarkie::CmpOperands::Int(1, 1));
+        let cmps = visitor.cmps();
+        assert!(!cmps.is_empty());
+        assert!(cmps
+            .iter()
+            .all(|(_, data)| autarkie::deserialize::<u8>(&mut data.as_slice()) != 0));
+        let mut visitor = visitor_for::<std::num::NonZeroU16>();
+        let near = std::num::NonZeroU16::new(300).unwrap();
+        // 301 is 1 away from the node, so the offset replacement is 1 - 1
+        near.__autarkie_cmps(&mut visitor, 0, autarkie::CmpOperands::Int(301, 1));
+        assert!(visitor
+            .cmps()
+            .iter()
+            .all(|(_, data)| autarkie::deserialize::<u16>(&mut data.as_slice()) != 0));
+    }
+    #[test]
+    fn cows_are_mutated_below() {
+        static PAIR: (u8, u16) = (1, 2);
+        let mut visitor = visitor_for::<ZeroCopy>();
+        let mut borrowed = ZeroCopy {
+            bytes: std::borrow::Cow::Borrowed(b"abc"),
+            pair: std::borrow::Cow::Borrowed(&PAIR),
+            trees: vec![Tree::Node(Box::new(Tree::Leaf(1))), Tree::Leaf(2)].into(),
+        };
+        borrowed.__autarkie_mutate(
+            &mut autarkie::MutationType::GenerateAppend(0),
+            &mut visitor,
+            VecDeque::from_iter([0]),
+        );
+        assert_eq!(borrowed.bytes.len(), 4);
+        let spliced = autarkie::serialize(&9_u8);
+        borrowed.__autarkie_mutate(
+            &mut autarkie::MutationType::Splice(&mut spliced.as_slice()),
+            &mut visitor,
+            VecDeque::from_iter([0, 0]),
+        );
+        assert_eq!(&borrowed.bytes[..3], b"\x09bc");
+        let spliced = autarkie::serialize(&7_u16);
+        borrowed.__autarkie_mutate(
+            &mut autarkie::MutationType::Splice(&mut spliced.as_slice()),
+            &mut visitor,
+            VecDeque::from_iter([1, 1]),
+        );
+        assert_eq!(*borrowed.pair, (1, 7));
+        assert_eq!(PAIR, (1, 2));
+        // recursive nodes are replaced by non-recursive ones whatever the rng picks
+        for seed in 0..64 {
+            let mut visitor = Visitor::new(
+                seed,
+                autarkie::DepthInfo {
+                    generate: 2,
+                    iterate: 2,
+                },
+                0,
+            );
+            ZeroCopy::__autarkie_register(&mut visitor, None, 0);
+            visitor.calculate_recursion();
+            let mut replaced = borrowed.clone();
+            replaced.__autarkie_mutate(
+                &mut autarkie::MutationType::RecursiveReplace,
+                &mut visitor,
+                VecDeque::from_iter([2]),
+            );
+            assert!(replaced
+                .trees
+                .iter()
+                .all(|tree| matches!(tree, Tree::Leaf(_))));
+            assert_eq!(replaced.trees[1], Tree::Leaf(2));
+        }
+        for _ in 0..100 {
+            let mut generated =
+                ZeroCopy::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+            generated.__autarkie_fields(&mut visitor, 0);
+            // deepest paths first, so replacing a node does not invalidate the remaining paths
+            for path in visitor.fields().into_iter().rev() {
+                let path = VecDeque::from_iter(path.iter().map(|((index, _), _)| *index));
+                generated.__autarkie_mutate(
+                    &mut autarkie::MutationType::GenerateReplace(0),
+                    &mut visitor,
+                    path,
+                );
+            }
+        }
+    }
+    #[test]
+    fn recursive_entries_of_maps_and_sets_are_replaced() {
+        let recursive = Tree::Node(Box::new(Tree::Leaf(1)));
+        for seed in 0..64 {
+            let mut visitor = Visitor::new(
+                seed,
+                autarkie::DepthInfo {
+                    generate: 2,
+                    iterate: 2,
+                },
+                0,
+            );
+            <BTreeMap<Tree, Tree>>::__autarkie_register(&mut visitor, None, 0);
+            <BTreeSet<Tree>>::__autarkie_register(&mut visitor, None, 0);
+            visitor.calculate_recursion();
+            let mut map = BTreeMap::from_iter([(recursive.clone(), recursive.clone())]);
+            map.__autarkie_mutate(
+                &mut autarkie::MutationType::RecursiveReplace,
+                &mut visitor,
+                VecDeque::new(),
+            );
+            assert_eq!(map.len(), 1);
+            assert!(map
+                .iter()
+                .all(|entry| matches!(entry, (Tree::Leaf(_), Tree::Leaf(_)))));
+            let mut set = BTreeSet::from_iter([recursive.clone()]);
+            set.__autarkie_mutate(
+                &mut autarkie::MutationType::RecursiveReplace,
+                &mut visitor,
+                VecDeque::new(),
+            );
+            assert_eq!(set.len(), 1);
+            assert!(set.iter().all(|tree| matches!(tree, Tree::Leaf(_))));
+        }
+    }
+    #[test]
+    fn numbers_use_interesting_values() {
+        let mut visitor = Visitor::new(
+            0,
+            autarkie::DepthInfo {
+                generate: 2,
+                iterate: 2,
+            },
+            0,
+        );
+        visitor.set_interesting_probability(1.0);
+        visitor.register_interesting(0xdead_beef);
+        let mut ints = BTreeSet::new();
+        let mut nan = false;
+        for _ in 0..1000 {
+            ints.insert(u32::__autarkie_generate(&mut visitor, &mut 0, 0, None).unwrap());
+            let float = f64::__autarkie_generate(&mut visitor, &mut 0, 0, None).unwrap();
+            nan |= float.is_nan();
+            let ranged = i16::__autarkie_generate(
+                &mut visitor,
+                &mut 0,
+                0,
+                Some(autarkie::GenerateSettings::Range(
+                    -3..=100,
+                    autarkie::Distribution::Uniform,
+                )),
+            )
+            .unwrap();
+            assert!((-3..=100).contains(&ranged));
+        }
+        for value in [0, 1, u32::MAX, 1 << 31, (1 << 16) - 1, 0xdead_beef] {
+            assert!(ints.contains(&value), "{value}");
+        }
+        assert!(nan);
+        visitor.set_interesting_probability(0.0);
+        assert!(u128::__autarkie_generate(&mut visitor, &mut 0, 0, None).is_some());
+    }
+    #[test]
+    fn arithmetic_mutates_numbers_in_place() {
+        use autarkie::{Arithmetic, MutationType};
+        let mut visitor = visitor_for::<Ranged>();
+        let mut number = 255_u8;
+        number.__autarkie_mutate(
+            &mut MutationType::Arithmetic(Arithmetic::Add(3)),
+            &mut visitor,
+            VecDeque::new(),
+        );
+        assert_eq!(number, 2);
+        number.__autarkie_mutate(
+            &mut MutationType::Arithmetic(Arithmetic::FlipBit(8 + 7)),
+            &mut visitor,
+            VecDeque::new(),
+        );
+        assert_eq!(number, 130);
+        let mut float = 2.5_f32;
+        float.__autarkie_mutate(
+            &mut MutationType::Arithmetic(Arithmetic::Negate),
+            &mut visitor,
+            VecDeque::new(),
+        );
+        assert_eq!(float, -2.5);
+        let ops = [
+            Arithmetic::Add(35),
+            Arithmetic::Sub(35),
+            Arithmetic::FlipBit(6),
+            Arithmetic::SwapBytes,
+            Arithmetic::Negate,
+        ];
+        for _ in 0..20 {
+            let mut generated =
+                Ranged::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+            for arithmetic in ops {
+                for field in 0..5 {
+                    generated.__autarkie_mutate(
+                        &mut MutationType::Arithmetic(arithmetic),
+                        &mut visitor,
+                        VecDeque::from_iter([field]),
+                    );
+                }
+                // constrained fields are repaired
+                assert!(!visitor.violates_constraints(&generated));
+                assert!((-5..=5).contains(&generated.small));
+                assert!((100..=200).contains(&generated.log));
+            }
+        }
+    }
+    #[test]
+    fn byte_leaves_are_swapped_out_and_repaired() {
+        use autarkie::MutationType;
+        let mut visitor = Visitor::new(
+            0,
+            autarkie::DepthInfo {
+                generate: 2,
+                iterate: 2,
+            },
+            0,
+        );
+        Text::__autarkie_register(&mut visitor, None, 0);
+        Constrained::__autarkie_register(&mut visitor, None, 0);
+        visitor.calculate_recursion();
+        let mut text = Text {
+            text: "hello".to_string(),
+            bytes: vec![1, 2, 3],
+        };
+        let mut bytes = vec![];
+        text.__autarkie_mutate(
+            &mut MutationType::Bytes(&mut bytes),
+            &mut visitor,
+            VecDeque::from_iter([0]),
+        );
+        assert_eq!(bytes, b"hello");
+        bytes = vec![b'f', 0xff, b'o'];
+        text.__autarkie_mutate(
+            &mut MutationType::Bytes(&mut bytes),
+            &mut visitor,
+            VecDeque::from_iter([0]),
+        );
+        // invalid UTF-8 is replaced
+        assert_eq!(text.text, "f\u{FFFD}o");
+        let mut bytes = vec![4, 5];
+        text.__autarkie_mutate(
+            &mut MutationType::Bytes(&mut bytes),
+            &mut visitor,
+            VecDeque::from_iter([1]),
+        );
+        assert_eq!(text.bytes, [4, 5]);
+        assert_eq!(bytes, [1, 2, 3]);
+        let mut constrained =
+            Constrained::__autarkie_generate(&mut visitor, &mut 0, 0, None).expect("generation");
+        let mut bytes = vec![0; 7];
+        constrained.__autarkie_mutate(
+            &mut MutationType::Bytes(&mut bytes),
+            &mut visitor,
+            VecDeque::from_iter([0]),
+        );
+        assert_eq!(constrained.fixed, [0; 4]);
+    }
+    #[test]
+    fn iterables_are_reordered_in_place() {
+        use autarkie::{MutationType, Reorder};
+        let mut visitor = Visitor::new(
+            0,
+            autarkie::DepthInfo {
+                generate: 2,
+                iterate: 2,
+            },
+            0,
+        );
+        let mut reorder = |items: &mut Vec<u8>, reorder: Reorder| {
+            items.__autarkie_mutate(
+                &mut MutationType::Reorder(reorder),
+                &mut visitor,
+                VecDeque::new(),
+            );
+        };
+        let mut items = vec![0, 1, 2, 3, 4, 5];
+        reorder(&mut items, Reorder::Swap(0, 5));
+        assert_eq!(items, [5, 1, 2, 3, 4, 0]);
+        reorder(&mut items, Reorder::Rotate(1..4, 1));
+        assert_eq!(items, [5, 2, 3, 1, 4, 0]);
+        reorder(&mut items, Reorder::Reverse(0..6));
+        assert_eq!(items, [0, 4, 1, 3, 2, 5]);
+        reorder(&mut items, Reorder::Duplicate(1));
+        assert_eq!(items, [0, 4, 4, 1, 3, 2, 5]);
+        reorder(&mut items, Reorder::Shuffle(0..7));
+        items.sort();
+        assert_eq!(items, [0, 1, 2, 3, 4, 4, 5]);
+        // fixed length iterables and VecDeque
+        let mut fixed = [1_u8, 2, 3, 4];
+        fixed.__autarkie_mutate(
+            &mut MutationType::Reorder(Reorder::Reverse(1..4)),
+            &mut visitor,
+            VecDeque::new(),
+        );
+        assert_eq!(fixed, [1, 4, 3, 2]);
+        let mut deque = VecDeque::from_iter([1_u8, 2, 3]);
+        deque.__autarkie_mutate(
+            &mut MutationType::Reorder(Reorder::Duplicate(2)),
+            &mut visitor,
+            VecDeque::new(),
+        );
+        assert_eq!(deque, [1, 2, 3, 3]);
+    }
+    #[test]
+    fn same_typed_nodes_are_copied_swapped_and_hoisted() {
+        use autarkie::MutationType;
+        let mut visitor = visitor_for::<Forest>();
+        let mut forest = Forest {
+            left: Tree::Leaf(2),
+            right: Tree::Node(Box::new(Tree::Node(Box::new(Tree::Leaf(1))))),
+        };
+        let copy = |forest: &mut Forest, visitor: &mut Visitor, path: &[usize]| {
+            let mut buffer = vec![];
+            forest.__autarkie_mutate(
+                &mut MutationType::Copy(&mut buffer),
+                visitor,
+                VecDeque::from_iter(path.iter().copied()),
+            );
+            buffer
+        };
+        let splice = |forest: &mut Forest, visitor: &mut Visitor, path: &[usize], data: &[u8]| {
+            forest.__autarkie_mutate(
+                &mut MutationType::Splice(&mut &data[..]),
+                visitor,
+                VecDeque::from_iter(path.iter().copied()),
+            );
+        };
+        let before = forest.clone();
+        let left = copy(&mut forest, &mut visitor, &[0]);
+        assert_eq!(left, autarkie::serialize(&Tree::Leaf(2)));
+        // copying leaves the node untouched
+        assert_eq!(forest, before);
+        // swap
+        let right = copy(&mut forest, &mut visitor, &[1]);
+        splice(&mut forest, &mut visitor, &[0], &right);
+        splice(&mut forest, &mut visitor, &[1], &left);
+        assert_eq!(forest.left, before.right);
+        assert_eq!(forest.right, before.left);
+        // hoist the innermost boxed tree onto its ancestor
+        forest.__autarkie_fields(&mut visitor, 0);
+        let mut boxed = visitor
+            .fields()
+            .into_iter()
+            .filter(|field| field.last().unwrap().1 == Box::<Tree>::__autarkie_id())
+            .map(|field| field.iter().map(|(i, _)| i.0).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        boxed.sort_by_key(|path| path.len());
+        assert_eq!(boxed.len(), 2);
+        assert!(boxed[1].starts_with(&boxed[0]));
+        let descendant = copy(&mut forest, &mut visitor, &boxed[1]);
+        splice(&mut forest, &mut visitor, &boxed[0], &descendant);
+        assert_eq!(forest.left, Tree::Node(Box::new(Tree::Leaf(1))));
+    }
+    #[test]
+    fn switching_variants_keeps_compatible_fields() {
+        use autarkie::MutationType;
+        let mut visitor = visitor_for::<Arith>();
+        let lit = |value| Box::new(Arith::Lit(value));
+        let expr = Arith::Add(lit(1), lit(2));
+        expr.__autarkie_fields(&mut visitor, 0);
+        let enums = visitor.enums();
+        visitor.fields();
+        // the root and both operands
+        assert_eq!(enums.len(), 3);
+        assert!(enums.contains(&(vec![], Arith::__autarkie_id(), 1)));
+        let mut seen = BTreeSet::new();
+        for _ in 0..200 {
+            for (path, _, _) in &enums {
+                let mut switched = expr.clone();
+                switched.__autarkie_mutate(
+                    &mut MutationType::SwitchVariant(None, 2),
+                    &mut visitor,
+                    VecDeque::from_iter(path.iter().copied()),
+                );
+                if !path.is_empty() {
+                    continue;
+                }
+                match switched {
+                    Arith::Lit(_) => {
+                        seen.insert(0);
+                    }
+                    Arith::Add(left, right) => {
+                        // switching to the same variant keeps it as is
+                        assert_eq!((left, right), (lit(1), lit(2)));
+                        seen.insert(1);
+                    }
+                    Arith::Sub(left, right) => {
+                        assert_eq!((left, right), (lit(1), lit(2)));
+                        seen.insert(2);
+                    }
+                    Arith::Neg { operand } => {
+                        assert_eq!(operand, lit(1));
+                        seen.insert(3);
+                    }
+                }
+            }
+        }
+        assert_eq!(seen, BTreeSet::from_iter([0, 1, 2, 3]));
+    }
+    #[test]
+    fn every_variant_and_option_can_be_swept() {
+        use autarkie::MutationType;
+        let mut visitor = visitor_for::<Optional>();
+        let optional = Optional {
+            value: Some(3),
+            arith: Arith::Sub(Box::new(Arith::Lit(1)), Box::new(Arith::Lit(2))),
+        };
+        optional.__autarkie_fields(&mut visitor, 0);
+        let enums = visitor.enums();
+        visitor.fields();
+        let option_id = Option::<u8>::__autarkie_id();
+        assert!(enums.contains(&(vec![0], option_id, 0)));
+        assert!(enums.contains(&(vec![1], Arith::__autarkie_id(), 2)));
+        let switch = |visitor: &mut Visitor, path: &[usize], variant| {
+            let mut switched = optional.clone();
+            switched.__autarkie_mutate(
+                &mut MutationType::SwitchVariant(Some(variant), 2),
+                visitor,
+                VecDeque::from_iter(path.iter().copied()),
+            );
+            switched
+        };
+        assert_eq!(visitor.variant_count(&Arith::__autarkie_id()), 4);
+        assert_eq!(switch(&mut visitor, &[0], 1).value, None);
+        assert_eq!(switch(&mut visitor, &[0], 0).value, Some(3));
+        let operand = |value| Box::new(Arith::Lit(value));
+        assert_eq!(
+            switch(&mut visitor, &[1], 1).arith,
+            Arith::Add(operand(1), operand(2))
+        );
+        assert_eq!(
+            switch(&mut visitor, &[1], 3).arith,
+            Arith::Neg {
+                operand: operand(1)
+            }
+        );
+        assert!(matches!(switch(&mut visitor, &[1], 0).arith, Arith::Lit(_)));
+        let mut none = Optional {
+            value: None,
+            ..optional.clone()
+        };
+        none.__autarkie_mutate(
+            &mut MutationType::SwitchVariant(None, 2),
+            &mut visitor,
+            VecDeque::from_iter([0]),
+        );
+        assert!(none.value.is_some());
+    }
+
+    #[test]
+    fn byte_operands_are_replaced_structurally() {
+        use autarkie::{CmpOperands, MutationType};
+        let mut visitor = visitor_for::<Packet>();
+        let packet = Packet {
+            method: "GET /".to_string(),
+            payload: b"xGETx".to_vec(),
+            magic: *b"GET\0",
+        };
+        let replace = |visitor: &mut Visitor, operands| {
+            packet.__autarkie_cmps(visitor, 0, operands);
+            visitor
+                .cmps()
+                .into_iter()
+                .map(|(path, data)| {
+                    let mut replaced = packet.clone();
+                    replaced.__autarkie_mutate(
+                        &mut MutationType::Splice(&mut data.as_slice()),
+                        visitor,
+                        path.iter().map(|(i, _)| i.0).collect(),
+                    );
+                    replaced
+                })
+                .collect::<Vec<_>>()
+        };
+        // the lengths differ, so only the nodes which can grow match
+        let replaced = replace(&mut visitor, CmpOperands::Bytes(b"POST", b"GET"));
+        assert_eq!(replaced.len(), 2);
+        assert!(replaced.iter().any(|p| p.method == "POST /"));
+        assert!(replaced.iter().any(|p| p.payload == b"xPOSTx"));
+        assert!(replaced.iter().all(|p| p.magic == *b"GET\0"));
+        let replaced = replace(&mut visitor, CmpOperands::Bytes(b"GET", b"PUT"));
+        assert_eq!(replaced.len(), 3);
+        assert!(replaced.iter().any(|p| p.magic == *b"PUT\0"));
+    }
+
+    #[test]
+    fn transformed_int_operands_are_written_back() {
+        use autarkie::{CmpOperands, CmpTransform};
+        let mut visitor = visitor_for::<Encoded>();
+        let encoded = Encoded {
+            swapped: 0x01020304,
+            narrow: 0xff,
+            signed: -1,
+            shifted: 1000,
+        };
+        let mut replacements = |left, right| {
+            encoded.__autarkie_cmps(&mut visitor, 0, CmpOperands::Int(left, right));
+            visitor
+                .transformed_cmps()
+                .into_iter()
+                .map(|(path, data, transform)| (path[0].0 .0, data, transform))
+                .collect::<Vec<_>>()
+        };
+        assert!(replacements(0x04030201, 0xaabbccdd).contains(&(
+            0,
+            autarkie::serialize(&0xddccbbaa_u32),
+            CmpTransform::ByteSwap
+        )));
+        assert!(replacements(u64::MAX, 5).contains(&(
+            1,
+            autarkie::serialize(&5_u8),
+            CmpTransform::SignExtend
+        )));
+        assert!(replacements(0xff, 0x10).contains(&(
+            2,
+            autarkie::serialize(&0x10_i8),
+            CmpTransform::SignExtend
+        )));
+        assert!(replacements(1010, 2000).contains(&(
+            3,
+            autarkie::serialize(&1990_u16),
+            CmpTransform::Offset
+        )));
+        let exact = replacements(1000, 2000)
+            .into_iter()
+            .filter(|(field, _, _)| *field == 3)
+            .collect::<Vec<_>>();
+        for (value, transform) in [
+            (2000_u16, CmpTransform::Identity),
+            (2001, CmpTransform::OffByOne),
+            (1999, CmpTransform::OffByOne),
+        ] {
+            assert!(exact.contains(&(3, autarkie::serialize(&value), transform)));
+        }
+    }
+
+    #[test]
+    fn rendered_operands_map_back_to_nodes() {
+        use autarkie::{CmpOperands, MutationType};
+        let mut visitor = visitor_for::<Query>();
+        let query = Query {
+            table: "users".to_string(),
+            id: 1234,
+            limit: Some(10),
+        };
+        let mut renderer = autarkie::Renderer::new();
+        render_query(&query, &mut renderer);
+        let (rendered, provenance) = renderer.finish();
+        assert_eq!(rendered, b"SELECT * FROM users WHERE id = 1234 LIMIT 10");
+        assert_eq!(provenance.span(&[1]), Some(31..35));
+        assert_eq!(provenance.node_at(&(42..44)), Some(&[2, 0][..]));
+        assert_eq!(provenance.node_at(&(0..6)), None);
+        let mut replace = |operands| {
+            autarkie::rendered_cmps(&query, &mut visitor, &rendered, &provenance, operands)
+                .into_iter()
+                .map(|(path, data, _)| {
+                    let mut replaced = query.clone();
+                    replaced.__autarkie_mutate(
+                        &mut MutationType::Splice(&mut data.as_slice()),
+                        &mut visitor,
+                        path.iter().map(|(i, _)| i.0).collect(),
+                    );
+                    replaced
+                })
+                .collect::<Vec<_>>()
+        };
+        // the target parsed the id and compared it as an integer
+        assert!(replace(CmpOperands::Int(1234, 77))
+            .iter()
+            .any(|replaced| replaced.id == 77 && replaced.table == "users"));
+        // or compared the text
+        assert!(replace(CmpOperands::Bytes(b"10", b"500"))
+            .iter()
+            .any(|replaced| replaced.limit == Some(500)));
+        assert!(replace(CmpOperands::Bytes(b"users", b"admins"))
+            .iter()
+            .any(|replaced| replaced.table == "admins"));
+        // the keywords were not rendered by any node
+        assert!(replace(CmpOperands::Bytes(b"SELECT", b"DELETE")).is_empty());
+    }
+
+    #[test]
+    fn harvested_tokens_are_ranked_and_capped() {
+        use autarkie::{TokenPool, MAX_AUTO_TOKENS, MAX_AUTO_TOKEN_LEN};
+        let mut pool = TokenPool::default();
+        assert!(pool.register_token(b"GET", 1));
+        assert!(pool.register_token(b"POST", 1));
+        assert!(!pool.register_token(b"POST", 1));
+        // empty, zeroed and overlong operands are not tokens
+        assert!(!pool.register_token(b"", 1));
+        assert!(!pool.register_token(&[0; 4], 1));
+        assert!(!pool.register_token(&[b'A'; MAX_AUTO_TOKEN_LEN + 1], 1));
+        assert_eq!(
+            pool.iter().collect::<Vec<_>>(),
+            vec![b"POST".as_slice(), b"GET".as_slice()]
+        );
+
+        // a full pool only makes room for tokens compared as often as its lowest ranked one
+        for i in 0..MAX_AUTO_TOKENS {
+            pool.register_token(format!("token{i}").as_bytes(), 2);
+        }
+        assert_eq!(pool.len(), MAX_AUTO_TOKENS);
+        assert_eq!(pool.iter().next(), Some(b"POST".as_slice()));
+        assert!(!pool.iter().any(|token| token == b"GET"));
+        assert!(!pool.register_token(b"PUT", 1));
+
+        // restarting takes over the persisted ranking, other pools add their tokens with their hits
+        let mut visitor = Visitor::new(
+            0,
+            autarkie::DepthInfo {
+                generate: 2,
+                iterate: 5,
+            },
+            0,
+        );
+        assert_eq!(visitor.merge_tokens(&pool).len(), MAX_AUTO_TOKENS);
+        assert_eq!(visitor.tokens(), &pool);
+        let mut other = TokenPool::default();
+        other.register_token(b"POST", 10);
+        other.register_token(b"DELETE", 10);
+        assert_eq!(visitor.merge_tokens(&other), vec![b"DELETE".to_vec()]);
+        assert_eq!(visitor.tokens().len(), MAX_AUTO_TOKENS);
+        assert_eq!(
+            visitor.tokens().iter().take(2).collect::<Vec<_>>(),
+            vec![b"POST".as_slice(), b"DELETE".as_slice()]
+        );
+        // hits are not summed, so merging the same pool again changes nothing
+        let merged = visitor.tokens().clone();
+        assert!(visitor.merge_tokens(&other).is_empty());
+        assert_eq!(visitor.tokens(), &merged);
+    }
 }